@@ -0,0 +1,203 @@
+//! A second Leptos app, served by the same server as the main one under
+//! [`ADMIN_BASE_PATH`]. Pavex tells components apart by their type, so the admin app has its own
+//! route index and app function types, and handlers that unwrap them before calling into
+//! `leptos_pavex`.
+use leptos::config::get_configuration;
+use leptos::prelude::{
+    view, ElementChild, GlobalAttributes, HydrationScripts, IntoAny, LeptosOptions,
+};
+use leptos_app::admin::{is_admin_server_fn, AdminApp, ADMIN_BASE_PATH};
+use leptos_meta::MetaTags;
+use leptos_pavex::base_path::BasePath;
+use leptos_pavex::leptos_routes::{
+    generate_leptos_routes_with_options, LeptosAppHandlers, LeptosRoutesOptions,
+};
+use leptos_pavex::pavex_helpers::{
+    AdditionalContextComponent, AdditionalContextServerFn, AppFunction,
+};
+use leptos_pavex::server_fn::ServerFnRegistry;
+use leptos_pavex::{generate_route_list_for_app, PavexRouteIndex, PavexRouteList};
+use leptos_router::Method as LeptosMethod;
+use pavex::blueprint::constructor::Lifecycle;
+use pavex::blueprint::router::GET;
+use pavex::blueprint::Blueprint;
+use pavex::http::Method;
+use pavex::request::body::RawIncomingBody;
+use pavex::request::path::{MatchedPathPattern, RawPathParams};
+use pavex::request::RequestHead;
+use pavex::response::Response;
+use pavex::{f, t};
+
+use crate::leptos::additional_context_components;
+
+/// The server functions of the admin app.
+pub const ADMIN_SERVER_FNS: ServerFnRegistry = ServerFnRegistry::new(is_admin_server_fn);
+
+/// The server functions of the main app, which are all the ones the admin app doesn't own.
+pub const MAIN_SERVER_FNS: ServerFnRegistry = ServerFnRegistry::new(is_main_server_fn);
+
+fn is_main_server_fn(path: &str) -> bool {
+    !is_admin_server_fn(path)
+}
+
+/// The admin app's routes and settings, built at startup like the main app's
+/// [`PavexRouteIndex`].
+#[derive(Clone, Debug)]
+pub struct AdminRouteIndex(pub PavexRouteIndex);
+
+impl AdminRouteIndex {
+    pub fn new(routes: PavexRouteList) -> Self {
+        Self(
+            PavexRouteIndex::from(routes)
+                .with_base_path(ADMIN_BASE_PATH)
+                .with_server_fns(ADMIN_SERVER_FNS),
+        )
+    }
+}
+
+/// The admin app, built for the current request.
+pub struct AdminAppFunction(pub AppFunction);
+
+pub fn generate_admin_app(
+    context: &AdditionalContextComponent,
+    options: LeptosOptions,
+    _req_head: &RequestHead,
+) -> AdminAppFunction {
+    let owner = context.owner();
+    // The admin app loads the bundle from under its own base path
+    let options = BasePath::new(ADMIN_BASE_PATH).hydration_options(options);
+    let fun = move || {
+        view! {
+            <!DOCTYPE html>
+            <html lang="en">
+                <head>
+                    <meta charset="utf-8"/>
+                    <meta name="viewport" content="width=device-width, initial-scale=1"/>
+                    <HydrationScripts options/>
+                    <MetaTags/>
+                </head>
+                <body>
+                    <AdminApp/>
+                </body>
+            </html>
+        }
+    };
+    AdminAppFunction(AppFunction::new(owner.with(fun).into_any()))
+}
+
+/// The routes defined in the admin app. `bp` writes them next to the server SDK, like the main
+/// app's.
+pub fn admin_route_list() -> PavexRouteList {
+    let conf = get_configuration(None).unwrap();
+    let leptos_options = conf.leptos_options;
+
+    let (routes, _) =
+        generate_route_list_for_app(additional_context_components, |context, req_head| {
+            generate_admin_app(context, leptos_options.clone(), req_head).0
+        });
+    routes
+}
+
+/// Every route [`register`] adds, for the main app to check its own routes against.
+pub fn admin_pavex_routes() -> Vec<(Method, String)> {
+    let mut routes = Vec::new();
+    for listing in admin_route_list().iter() {
+        for method in listing.methods() {
+            let method = match method {
+                LeptosMethod::Get => Method::GET,
+                LeptosMethod::Post => Method::POST,
+                LeptosMethod::Put => Method::PUT,
+                LeptosMethod::Delete => Method::DELETE,
+                LeptosMethod::Patch => Method::PATCH,
+            };
+            routes.push((method, listing.path().to_string()));
+        }
+    }
+    let base_path = BasePath::new(ADMIN_BASE_PATH);
+    for (path, method) in ADMIN_SERVER_FNS.paths() {
+        routes.push((method, base_path.prefix(path)));
+    }
+    routes.push((Method::GET, ADMIN_ASSETS_PATH.to_string()));
+    routes
+}
+
+/// The admin app's assets and not found page.
+const ADMIN_ASSETS_PATH: &str = "/admin/*path";
+
+pub fn register(bp: &mut Blueprint) {
+    bp.prebuilt(t!(crate::admin::AdminRouteIndex))
+        .clone_if_necessary();
+    bp.constructor(
+        f!(crate::admin::generate_admin_app),
+        Lifecycle::RequestScoped,
+    );
+
+    let options = LeptosRoutesOptions {
+        handlers: LeptosAppHandlers {
+            register_page: |bp, method, path| {
+                bp.route(method, path, f!(crate::admin::render_route));
+            },
+            register_server_fn: |bp, method, path| {
+                bp.route(method, path, f!(crate::admin::handle_server_fns));
+            },
+            server_fns: ADMIN_SERVER_FNS,
+        },
+        base_path: BasePath::new(ADMIN_BASE_PATH),
+        ..Default::default()
+    };
+    generate_leptos_routes_with_options(&admin_route_list(), bp, options);
+    // Anything else under the base path is one of the admin app's assets, or its not found page
+    bp.route(
+        GET,
+        ADMIN_ASSETS_PATH,
+        f!(crate::admin::file_and_error_handler),
+    );
+}
+
+// Pavex injects each of these separately
+#[allow(clippy::too_many_arguments)]
+pub async fn render_route(
+    routes: &AdminRouteIndex,
+    options: &LeptosOptions,
+    req_head: &RequestHead,
+    req_body: RawIncomingBody,
+    matched_path: &MatchedPathPattern,
+    path_params: &RawPathParams<'_, '_>,
+    context: AdditionalContextComponent,
+    app_fn: AdminAppFunction,
+) -> Response {
+    leptos_pavex::render_route(
+        &routes.0,
+        options,
+        req_head,
+        req_body,
+        matched_path,
+        path_params,
+        context,
+        app_fn.0,
+    )
+    .await
+}
+
+pub async fn handle_server_fns(
+    routes: &AdminRouteIndex,
+    req_head: RequestHead,
+    req_body: RawIncomingBody,
+    context: AdditionalContextServerFn,
+) -> Response {
+    leptos_pavex::server_fn::handle_server_fns(&routes.0, req_head, req_body, context).await
+}
+
+pub async fn file_and_error_handler(
+    routes: &AdminRouteIndex,
+    options: &LeptosOptions,
+    req_head: RequestHead,
+    req_body: RawIncomingBody,
+    context: AdditionalContextComponent,
+    app_fn: AdminAppFunction,
+) -> Response {
+    leptos_pavex::file_helpers::file_and_error_handler(
+        &routes.0, options, req_head, req_body, context, app_fn.0,
+    )
+    .await
+}
@@ -1,8 +1,12 @@
+use app::admin::admin_route_list;
 use app::blueprint;
+use app::routes::leptos_route_list;
 use cargo_px_env::generated_pkg_manifest_path;
+use leptos_pavex::route_artifact::{check_route_list, write_route_list};
 use pavex_cli_client::Client;
 use std::env::args;
 use std::error::Error;
+use std::path::PathBuf;
 
 /// Generate the `server_sdk` crate using Pavex's CLI.
 ///
@@ -10,17 +14,45 @@ use std::error::Error;
 /// into a "server SDK" that can be used by the final API server binary to launch
 /// the application.
 ///
+/// The Leptos routes are written to `leptos_routes.json` in the server SDK crate,
+/// and the admin app's to `leptos_admin_routes.json`, for the server binary to load
+/// at startup.
+///
 /// If `--check` is passed as an argument, it only verifies that the server SDK
-/// crate is up-to-date. An error is returned if it isn't.
+/// crate and the Leptos route list are up-to-date. An error is returned if they aren't.
 fn main() -> Result<(), Box<dyn Error>> {
-    let generated_dir = generated_pkg_manifest_path()?.parent().unwrap().into();
+    let generated_dir: PathBuf = generated_pkg_manifest_path()?.parent().unwrap().into();
+    let route_lists = [
+        (
+            generated_dir.join("leptos_routes.json"),
+            leptos_route_list(),
+        ),
+        (
+            generated_dir.join("leptos_admin_routes.json"),
+            admin_route_list(),
+        ),
+    ];
+    let check = args().any(|arg| arg == "--check");
+
     let mut cmd = Client::new().generate(blueprint(), generated_dir);
-    if args().any(|arg| arg == "--check") {
+    if check {
         cmd = cmd.check()
     };
     if let Err(e) = cmd.execute() {
         eprintln!("{e}");
         std::process::exit(1);
     }
+
+    for (route_list_path, routes) in route_lists {
+        let result = if check {
+            check_route_list(&route_list_path, &routes)
+        } else {
+            write_route_list(&route_list_path, &routes)
+        };
+        if let Err(e) = result {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    }
     Ok(())
 }
@@ -1,9 +1,9 @@
-use crate::{configuration, routes, telemetry};
+use crate::{admin, configuration, routes, telemetry};
 use pavex::blueprint::constructor::Lifecycle;
 use pavex::blueprint::linter::Lint;
 use pavex::blueprint::Blueprint;
-use pavex::{f, t};
 use pavex::kit::ApiKit;
+use pavex::{f, t};
 
 /// The main blueprint, containing all the routes, middlewares, constructors and error handlers
 /// required by our API.
@@ -20,12 +20,16 @@ pub fn blueprint() -> Blueprint {
     bp.constructor(
         f!(super::leptos::additional_context_serverfn),
         Lifecycle::RequestScoped,
-    ).ignore(Lint::Unused);
+    )
+    .ignore(Lint::Unused);
     bp.constructor(f!(super::leptos::generate_app), Lifecycle::RequestScoped);
 
-    bp.prebuilt(t!(leptos_config::LeptosOptions)).clone_if_necessary();
-    bp.prebuilt(t!(std::vec::Vec<leptos_pavex::PavexRouteListing>)).clone_if_necessary();
+    bp.prebuilt(t!(leptos_config::LeptosOptions))
+        .clone_if_necessary();
+    bp.prebuilt(t!(leptos_pavex::PavexRouteIndex))
+        .clone_if_necessary();
 
     routes::register(&mut bp);
+    admin::register(&mut bp);
     bp
 }
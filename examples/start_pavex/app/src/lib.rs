@@ -1,3 +1,4 @@
+pub mod admin;
 mod blueprint;
 pub mod configuration;
 pub mod leptos;
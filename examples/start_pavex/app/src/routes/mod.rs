@@ -1,32 +1,58 @@
 pub mod greet;
 pub mod ping;
+pub mod server_fns;
 
-use http::Request;
 use leptos::config::get_configuration;
-use leptos_pavex::leptos_routes::generate_leptos_routes;
+use leptos_pavex::leptos_routes::{
+    generate_leptos_routes_with_options, LeptosAppHandlers, LeptosRoutesOptions, ServerFnMiddleware,
+};
+use leptos_pavex::{generate_route_list_for_app, PavexRouteList};
 use pavex::blueprint::{router::GET, Blueprint};
 use pavex::f;
-use pavex::request::RequestHead;
+use pavex::http::Method;
 
-use crate::leptos::generate_app;
+use crate::admin::{admin_pavex_routes, MAIN_SERVER_FNS};
+use crate::leptos::{additional_context_components, generate_app};
 
-pub fn register(bp: &mut Blueprint) {
+/// The routes defined in the Leptos app. `bp` writes them next to the server SDK, so the server
+/// can load them instead of generating them again.
+pub fn leptos_route_list() -> PavexRouteList {
     let conf = get_configuration(None).unwrap();
     let leptos_options = conf.leptos_options;
 
-    // Generate routes for routes defined in Leptos for components and server fns
-    let mock_request = Request::builder()
-        .uri("https://www.leptos.dev/about")
-        .body(())
-        .unwrap();
-    let mock_req_head: RequestHead = mock_request.into_parts().0.into();
-    let routes = leptos_pavex::generate_route_list(generate_app(
-        &crate::leptos::additional_context_components(&mock_req_head),
-        leptos_options,
-        &mock_req_head,
-    ));
-    generate_leptos_routes(&routes, bp);
+    let (routes, _) =
+        generate_route_list_for_app(additional_context_components, |context, req_head| {
+            generate_app(context, leptos_options.clone(), req_head)
+        });
+    routes
+}
+
+pub fn register(bp: &mut Blueprint) {
+    // Generate routes for routes defined in Leptos for components and server fns. The routes
+    // below are listed too, so they're checked for conflicts with the generated ones
+    let options = LeptosRoutesOptions {
+        // The admin app's server functions are registered with the admin app
+        handlers: LeptosAppHandlers {
+            server_fns: MAIN_SERVER_FNS,
+            ..Default::default()
+        },
+        pavex_routes: [
+            (Method::GET, "/api/ping".into()),
+            (Method::GET, "/api/greet/:name".into()),
+        ]
+        .into_iter()
+        .chain(admin_pavex_routes())
+        .collect(),
+        server_fn_middleware: vec![ServerFnMiddleware::for_server_fn::<
+            leptos_app::functions::RedirectTo,
+        >(|bp| {
+            bp.wrap(f!(self::server_fns::log_redirects));
+        })],
+        ..Default::default()
+    };
+    generate_leptos_routes_with_options(&leptos_route_list(), bp, options);
     bp.route(GET, "/api/ping", f!(self::ping::get));
     bp.route(GET, "/api/greet/:name", f!(self::greet::get));
-    bp.route(GET, "/*path", f!(leptos_pavex::file_helpers::serve_files));
+    // Serve assets, and render the app's own not found page for anything else
+    bp.fallback(f!(leptos_pavex::file_helpers::file_and_error_handler));
 }
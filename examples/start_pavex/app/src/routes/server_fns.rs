@@ -0,0 +1,23 @@
+use pavex::http::header::LOCATION;
+use pavex::middleware::Next;
+use pavex::request::path::MatchedPathPattern;
+use pavex::response::Response;
+use std::future::IntoFuture;
+
+/// Log where a server function sent the caller. It's registered around the server functions that
+/// redirect, in `register`, rather than around every route. Server functions are routed by their
+/// full path, so the matched pattern is the path of the one that ran.
+pub async fn log_redirects<C>(next: Next<C>, server_fn: MatchedPathPattern) -> Response
+where
+    C: IntoFuture<Output = Response>,
+{
+    let response = next.await;
+    if let Some(location) = response.headers().get(LOCATION) {
+        tracing::info!(
+            server_fn = server_fn.inner(),
+            location = ?location,
+            "Server function redirected"
+        );
+    }
+    response
+}
@@ -1,8 +1,8 @@
 use http::status::StatusCode;
 use miette::Diagnostic;
 use serde::{Deserialize, Serialize};
-use thiserror::Error;
 use strum_macros::EnumString;
+use thiserror::Error;
 #[derive(Debug, Clone, EnumString, Error, Diagnostic, Serialize, Deserialize)]
 pub enum EcommerceAppError {
     #[error("Not Found")]
@@ -27,4 +27,11 @@ impl EcommerceAppError {
             EcommerceAppError::TomlError(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
-}
\ No newline at end of file
+}
+// Failed server functions are answered with the same status as the error page
+#[cfg(feature = "ssr")]
+impl leptos_pavex::error_status::ErrorStatus for EcommerceAppError {
+    fn status_code(&self) -> StatusCode {
+        EcommerceAppError::status_code(self)
+    }
+}
@@ -2,7 +2,6 @@
 //! for the ecommerce platform.
 mod models;
 
-
 use cfg_if::cfg_if;
 
 #[rustfmt::skip]
@@ -30,13 +29,10 @@ cfg_if! {
                         
                         embed_migrations!("./../server/migrations");
 
-                        let conn = Arc::new(Mutex::new(
+                        Arc::new(Mutex::new(
                             Connection::open("./../db.db3")
                                 .expect("Failed to connect to DB")
-    )
-                        );
-
-                        conn
+                        ))
                     }));
                     if let Err(e) = PTR.compare_exchange(null_mut(), p, Release, Acquire) {
                         // Safety: p comes from Box::into_raw right above,
@@ -46,7 +42,7 @@ cfg_if! {
                         p = e;
                     }
                 }
-                Ok(Arc::clone(&*(unsafe { &*p })))
+                Ok(Arc::clone(unsafe { &*p }))
 
             } else {
                 let raw_conn = match use_context::<Arc<Mutex<Connection>>>(){
@@ -2,10 +2,22 @@ use wasm_bindgen::prelude::wasm_bindgen;
 
 #[wasm_bindgen]
 pub fn hydrate() {
+    use leptos_app::admin::{is_admin_path, AdminApp, ADMIN_BASE_PATH};
     use leptos_app::pages::App;
     // initializes logging using the `log` crate
     _ = console_log::init_with_level(log::Level::Debug);
     console_error_panic_hook::set_once();
     //leptos::leptos_dom::HydrationCtx::stop_hydrating();
-    leptos::mount::hydrate_body(App);
+    // Both apps are in this bundle, the page says which one it belongs to
+    let path = leptos::prelude::window()
+        .location()
+        .pathname()
+        .unwrap_or_default();
+    if is_admin_path(&path) {
+        // The admin app's server functions are served under its base path
+        leptos::server_fn::client::set_server_url(ADMIN_BASE_PATH);
+        leptos::mount::hydrate_body(AdminApp);
+    } else {
+        leptos::mount::hydrate_body(App);
+    }
 }
@@ -0,0 +1,71 @@
+use leptos::prelude::*;
+use leptos::{component, view, IntoView};
+use leptos_meta::*;
+use leptos_pavex_macro::server;
+use leptos_router::components::*;
+use leptos_router::StaticSegment;
+
+/// The admin app is served under this path, next to the main app in the same server. The server
+/// and the client both read it from here, so they can't disagree.
+pub const ADMIN_BASE_PATH: &str = "/admin";
+
+/// Whether a page at `path` belongs to the admin app.
+pub fn is_admin_path(path: &str) -> bool {
+    path == ADMIN_BASE_PATH
+        || path
+            .strip_prefix(ADMIN_BASE_PATH)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Whether the server function at `path` belongs to the admin app. They all share the
+/// `/_server/admin` prefix.
+pub fn is_admin_server_fn(path: &str) -> bool {
+    path.starts_with("/_server/admin/")
+}
+
+/// Counts how often the dashboard asked for its stats.
+#[server(prefix = "/_server/admin", endpoint = "stats")]
+pub async fn admin_stats() -> Result<usize, ServerFnError> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+    Ok(CALLS.fetch_add(1, Ordering::Relaxed) + 1)
+}
+
+/// A second app, served under [`ADMIN_BASE_PATH`] by the same server as [`App`](crate::pages::App).
+#[component]
+pub fn AdminApp() -> impl IntoView {
+    provide_meta_context();
+
+    view! {
+        <Title text="Leptos Pavex Admin"/>
+        // The routes are declared under the base path, the `base` only keeps the client to the
+        // links within the app
+        <Router base=ADMIN_BASE_PATH>
+            <FlatRoutes fallback=||{view!{<p>"Not found"</p>}}>
+                <Route path=StaticSegment(ADMIN_BASE_PATH.trim_start_matches('/')) view=Dashboard/>
+            </FlatRoutes>
+        </Router>
+    }
+}
+
+#[component]
+fn Dashboard() -> impl IntoView {
+    let stats = Resource::new(|| (), |_| admin_stats());
+    let refresh = Action::new(|_: &()| admin_stats());
+    let calls = move || {
+        refresh
+            .value()
+            .get()
+            .or_else(|| stats.get())
+            .and_then(Result::ok)
+    };
+    view! {
+        <main>
+            <h1>"Admin"</h1>
+            <Suspense fallback=|| view! { <p>"Loading the stats"</p> }>
+                <p id="calls">"Stats were asked for " {move || calls().unwrap_or_default()} " times"</p>
+            </Suspense>
+            <button on:click=move |_| { refresh.dispatch(()); }>"Refresh"</button>
+        </main>
+    }
+}
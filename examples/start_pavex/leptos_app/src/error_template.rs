@@ -4,7 +4,6 @@ use leptos::prelude::*;
 #[cfg(feature = "ssr")]
 use leptos_pavex::response_options::ResponseOptions;
 
-
 // A basic function to display errors served by the error boundaries.
 // Feel free to do more complicated things here than just displaying the error.
 #[component]
@@ -1,2 +1,2 @@
 pub mod test;
-pub use test::*;
\ No newline at end of file
+pub use test::*;
@@ -1,7 +1,13 @@
 use leptos_pavex_macro::server;
 //use leptos::prelude::server;
 use leptos::prelude::ServerFnError;
-#[server(endpoint="greet")]
-pub async fn greetings(name: String) -> Result<String, ServerFnError>{
+#[server(endpoint = "greet")]
+pub async fn greetings(name: String) -> Result<String, ServerFnError> {
     Ok(format!("Salutations {name}"))
-}
\ No newline at end of file
+}
+/// Sends the caller to `to`, for trying out redirects from server functions.
+#[server(endpoint = "redirect_to")]
+pub async fn redirect_to(to: String) -> Result<(), ServerFnError> {
+    leptos_pavex::redirect(&to);
+    Ok(())
+}
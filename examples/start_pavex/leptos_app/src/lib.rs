@@ -1,4 +1,5 @@
 #![allow(non_snake_case)]
+pub mod admin;
 pub mod components;
 pub mod error_template;
 pub mod functions;
@@ -9,4 +9,4 @@ fn About() -> impl IntoView {
             <h1>"About Page"</h1>
         </main>
     }
-}
\ No newline at end of file
+}
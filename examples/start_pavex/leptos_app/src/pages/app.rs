@@ -1,4 +1,4 @@
-use crate::pages::{home::__Home, __About};
+use crate::pages::{__About, __Blocking, home::__Home};
 use leptos::prelude::*;
 use leptos::{component, view, IntoView};
 use leptos_meta::*;
@@ -12,7 +12,7 @@ use leptos_router::*;
 pub fn App() -> impl IntoView {
     // Provides context that manages stylesheets, titles, meta tags, etc.
     provide_meta_context();
-    
+
     view! {
         // <Stylesheet id="leptos" href="/pkg/start-pavex.css"/>
         <link rel="stylesheet" href="/pkg/start_pavex.css"/>
@@ -22,13 +22,16 @@ pub fn App() -> impl IntoView {
         <Title text="Leptos Pavex Starter"/>
 
         // content for this welcome page
+        // The pinned Leptos can't render the outlets of nested `<Routes/>` on the server, so the
+        // pages are flat routes
         <Router>
 
-                <Routes fallback=||{view!{<p>"Not found"</p>}}>
+                <FlatRoutes fallback=||{view!{<p>"Not found"</p>}}>
                 <Route path=StaticSegment("/") view=__Home/>
                 <Route path=StaticSegment("/about") view=__About/>
+                <Route path=StaticSegment("/blocking") view=__Blocking ssr=SsrMode::PartiallyBlocked/>
 
-            </Routes>
+            </FlatRoutes>
         </Router>
     }
 }
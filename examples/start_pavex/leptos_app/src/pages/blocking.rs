@@ -0,0 +1,16 @@
+use leptos::prelude::{ElementChild, Get, GlobalAttributes, Resource, Suspense};
+use leptos::{component, view, IntoView};
+
+/// Renders a partially-blocked page, so the resource's content is in the HTML the server sends
+/// instead of streaming in after a fallback.
+#[component]
+fn Blocking() -> impl IntoView {
+    let message = Resource::new(|| (), |_| async move { "Loaded on the server".to_string() });
+    view! {
+        <main>
+            <Suspense fallback=|| view! { <p>"Loading the message"</p> }>
+                {move || message.get().map(|message| view! { <p id="message">{message}</p> })}
+            </Suspense>
+        </main>
+    }
+}
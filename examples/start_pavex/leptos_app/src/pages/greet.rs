@@ -2,12 +2,11 @@
 
 // use crate::functions::greetings;
 
-
 // #[component]
 // pub async fn greet() -> impl IntoView{
 //     let greeting =
 //         Resource::new(|| (), move |_| async move { greetings().await });
 //     view!{
-    
+
 //     }
 // }
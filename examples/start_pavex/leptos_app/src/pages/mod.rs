@@ -4,5 +4,7 @@ pub mod app;
 pub use app::*;
 pub mod about;
 pub use about::*;
+pub mod blocking;
+pub use blocking::*;
 pub mod greet;
-// pub use greet::*;
\ No newline at end of file
+// pub use greet::*;
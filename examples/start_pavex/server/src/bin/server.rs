@@ -1,9 +1,11 @@
 use anyhow::Context;
+use app::admin::{AdminRouteIndex, MAIN_SERVER_FNS};
 use app::leptos::{additional_context_components, generate_app};
-use http::Request;
 use leptos::prelude::get_configuration;
-use leptos_pavex::generate_route_list;
-use pavex::request::RequestHead;
+use leptos_pavex::pavex_helpers::AppRenderer;
+use leptos_pavex::route_artifact::route_list_from_json;
+use leptos_pavex::static_routes::build_static_routes;
+use leptos_pavex::PavexRouteIndex;
 use pavex::server::{Server, ServerHandle, ShutdownMode};
 use pavex_tracing::fields::{error_details, error_message, ERROR_DETAILS, ERROR_MESSAGE};
 use server::{
@@ -43,19 +45,41 @@ async fn _main() -> anyhow::Result<()> {
     let conf = get_configuration(None).unwrap();
     let leptos_options = conf.leptos_options;
 
-    // Generate Leptos Route list
-    let mock_request = Request::builder()
-        .uri("https://www.leptos.dev/about")
-        .body(())
-        .unwrap();
-    let mock_req_head: RequestHead = mock_request.into_parts().0.into();
-    let routes = generate_route_list(generate_app(
-        &additional_context_components(&mock_req_head),
-        leptos_options.clone(),
-        &mock_req_head,
-    ));
+    // Load the Leptos route list `bp` wrote when it generated the server SDK. It's embedded in the
+    // binary, so the server doesn't need the source tree around at runtime
+    let routes = route_list_from_json(include_str!("../../../server_sdk/leptos_routes.json"))
+        .context("Failed to load the Leptos route list, run `cargo px build` to generate it")?;
 
-    let application_state = build_application_state(routes, leptos_options, config.app).await;
+    // Prerender the static routes, so they don't have to be rendered on every request. The static
+    // routes in the route list already know their paths, so there are no static params to pass
+    build_static_routes(
+        &leptos_options,
+        &routes,
+        &Default::default(),
+        additional_context_components,
+        |context, req_head| generate_app(context, leptos_options.clone(), req_head),
+    )
+    .await
+    .context("Failed to build the static routes")?;
+
+    // Incremental pages are rendered once and served to everyone, so they need to build the context
+    // and app themselves
+    let renderer = AppRenderer::new(additional_context_components, {
+        let leptos_options = leptos_options.clone();
+        move |context, req_head| generate_app(context, leptos_options.clone(), req_head)
+    });
+    let routes = PavexRouteIndex::from(routes)
+        .with_app_renderer(renderer)
+        .with_server_fns(MAIN_SERVER_FNS);
+
+    // The admin app is served by the same server, under its own base path
+    let admin_routes =
+        route_list_from_json(include_str!("../../../server_sdk/leptos_admin_routes.json"))
+            .context("Failed to load the admin route list, run `cargo px build` to generate it")?;
+    let admin_routes = AdminRouteIndex::new(admin_routes);
+
+    let application_state =
+        build_application_state(routes, leptos_options, config.app, admin_routes).await;
     let tcp_listener = config
         .server
         .listener()
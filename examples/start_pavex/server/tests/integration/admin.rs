@@ -0,0 +1,38 @@
+use crate::helpers::TestApi;
+use pavex::http::StatusCode;
+
+#[tokio::test]
+async fn admin_app_is_served_under_its_base_path() {
+    let api = TestApi::spawn().await;
+
+    let response = api.get_page("/admin").await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.text().await.unwrap();
+    assert!(body.contains("<h1>Admin</h1>"));
+    // The client is loaded from under the base path too
+    assert!(body.contains("/admin/pkg/"));
+}
+
+#[tokio::test]
+async fn unknown_admin_pages_get_the_admin_not_found_page() {
+    let api = TestApi::spawn().await;
+
+    let response = api.get_page("/admin/missing").await;
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let body = response.text().await.unwrap();
+    assert!(body.contains("Leptos Pavex Admin"));
+}
+
+#[tokio::test]
+async fn admin_server_fns_are_only_served_by_the_admin_app() {
+    let api = TestApi::spawn().await;
+
+    let response = api.post_admin_server_fn("stats", "").await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // The main app doesn't answer them, so they're only reachable under the admin base path
+    let response = api.post_server_fn("admin/stats", "").await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
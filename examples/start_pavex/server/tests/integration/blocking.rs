@@ -0,0 +1,14 @@
+use crate::helpers::TestApi;
+use pavex::http::StatusCode;
+
+#[tokio::test]
+async fn partially_blocked_route_renders_its_resources_in_place() {
+    let api = TestApi::spawn().await;
+
+    let response = api.get_page("/blocking").await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.text().await.unwrap();
+    assert!(body.contains("Loaded on the server"));
+    assert!(!body.contains("Loading the message"));
+}
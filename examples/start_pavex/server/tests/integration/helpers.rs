@@ -1,3 +1,9 @@
+use app::admin::{AdminRouteIndex, MAIN_SERVER_FNS};
+use app::leptos::{additional_context_components, generate_app};
+use leptos::prelude::get_configuration;
+use leptos_pavex::pavex_helpers::AppRenderer;
+use leptos_pavex::route_artifact::route_list_from_json;
+use leptos_pavex::PavexRouteIndex;
 use pavex::server::Server;
 use server::configuration::{ApplicationProfile, Config};
 use server_sdk::{build_application_state, run};
@@ -15,7 +21,26 @@ impl TestApi {
         Self::init_telemetry();
         let config = Self::get_config();
 
-        let application_state = build_application_state().await;
+        // Build the state the way the server binary does, from the route lists `bp` wrote
+        let leptos_options = get_configuration(None)
+            .expect("Failed to load the Leptos configuration")
+            .leptos_options;
+        let routes = route_list_from_json(include_str!("../../../server_sdk/leptos_routes.json"))
+            .expect("Failed to load the Leptos route list");
+        let renderer = AppRenderer::new(additional_context_components, {
+            let leptos_options = leptos_options.clone();
+            move |context, req_head| generate_app(context, leptos_options.clone(), req_head)
+        });
+        let routes = PavexRouteIndex::from(routes)
+            .with_app_renderer(renderer)
+            .with_server_fns(MAIN_SERVER_FNS);
+        let admin_routes =
+            route_list_from_json(include_str!("../../../server_sdk/leptos_admin_routes.json"))
+                .expect("Failed to load the admin route list");
+        let admin_routes = AdminRouteIndex::new(admin_routes);
+
+        let application_state =
+            build_application_state(routes, leptos_options, config.app, admin_routes).await;
 
         let tcp_listener = config
             .server
@@ -31,7 +56,11 @@ impl TestApi {
 
         TestApi {
             api_address: format!("http://{}:{}", config.server.ip, address.port()),
-            api_client: reqwest::Client::new(),
+            // Redirects are part of what's under test, so they're never followed
+            api_client: reqwest::Client::builder()
+                .redirect(reqwest::redirect::Policy::none())
+                .build()
+                .expect("Failed to build the API client"),
         }
     }
 
@@ -70,7 +99,62 @@ impl TestApi {
 impl TestApi {
     pub async fn get_ping(&self) -> reqwest::Response {
         self.api_client
-            .get(&format!("{}/api/ping", &self.api_address))
+            .get(format!("{}/api/ping", &self.api_address))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn get_page(&self, path: &str) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}{path}", &self.api_address))
+            .header("Accept", "text/html")
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    /// Call a server function the way a plain HTML form posting to it would.
+    pub async fn post_server_fn_form(
+        &self,
+        endpoint: &str,
+        body: &str,
+        referer: Option<&str>,
+    ) -> reqwest::Response {
+        let mut request = self
+            .api_client
+            .post(format!("{}/_server/{endpoint}", &self.api_address))
+            .header("Accept", "text/html")
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .body(body.to_string());
+        if let Some(referer) = referer {
+            request = request.header("Referer", referer);
+        }
+        request.send().await.expect("Failed to execute request.")
+    }
+
+    /// Call a server function the way the server function client does.
+    pub async fn post_server_fn(&self, endpoint: &str, body: &str) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/_server/{endpoint}", &self.api_address))
+            .header("Accept", "application/json")
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .body(body.to_string())
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    /// Call a server function of the admin app, which are served under its base path.
+    pub async fn post_admin_server_fn(&self, endpoint: &str, body: &str) -> reqwest::Response {
+        self.api_client
+            .post(format!(
+                "{}/admin/_server/admin/{endpoint}",
+                &self.api_address
+            ))
+            .header("Accept", "application/json")
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .body(body.to_string())
             .send()
             .await
             .expect("Failed to execute request.")
@@ -1,2 +1,5 @@
+mod admin;
+mod blocking;
 mod helpers;
 mod ping;
+mod redirect;
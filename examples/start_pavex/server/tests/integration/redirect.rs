@@ -0,0 +1,61 @@
+use crate::helpers::TestApi;
+use pavex::http::StatusCode;
+
+const REDIRECT_HEADER: &str = "serverfnredirect";
+
+#[tokio::test]
+async fn form_post_is_sent_back_to_the_referer() {
+    let api = TestApi::spawn().await;
+
+    let response = api
+        .post_server_fn_form("greet", "name=Ferris", Some("/greet"))
+        .await;
+
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert_eq!(response.headers()["location"], "/greet");
+}
+
+#[tokio::test]
+async fn form_post_without_a_referer_is_not_redirected() {
+    let api = TestApi::spawn().await;
+
+    let response = api.post_server_fn_form("greet", "name=Ferris", None).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().get("location").is_none());
+}
+
+#[tokio::test]
+async fn form_post_follows_the_server_fn_redirect_over_the_referer() {
+    let api = TestApi::spawn().await;
+
+    let response = api
+        .post_server_fn_form("redirect_to", "to=%2Fthanks", Some("/greet"))
+        .await;
+
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert_eq!(response.headers()["location"], "/thanks");
+    assert!(response.headers().get(REDIRECT_HEADER).is_none());
+}
+
+#[tokio::test]
+async fn client_call_gets_the_redirect_header() {
+    let api = TestApi::spawn().await;
+
+    let response = api.post_server_fn("redirect_to", "to=%2Fthanks").await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["location"], "/thanks");
+    assert!(response.headers().get(REDIRECT_HEADER).is_some());
+}
+
+#[tokio::test]
+async fn client_call_without_a_redirect_is_left_alone() {
+    let api = TestApi::spawn().await;
+
+    let response = api.post_server_fn("greet", "name=Ferris").await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().get("location").is_none());
+    assert!(response.headers().get(REDIRECT_HEADER).is_none());
+}
@@ -7,19 +7,22 @@ struct ServerState {
     application_state: ApplicationState,
 }
 pub struct ApplicationState {
-    s0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
+    s0: leptos_pavex::PavexRouteIndex,
     s1: leptos_config::LeptosOptions,
     s2: app::configuration::AppConfig,
+    s3: app::admin::AdminRouteIndex,
 }
 pub async fn build_application_state(
-    v0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
+    v0: leptos_pavex::PavexRouteIndex,
     v1: leptos_config::LeptosOptions,
     v2: app::configuration::AppConfig,
+    v3: app::admin::AdminRouteIndex,
 ) -> crate::ApplicationState {
     crate::ApplicationState {
         s0: v0,
         s1: v1,
         s2: v2,
+        s3: v3,
     }
 }
 pub fn run(
@@ -35,11 +38,15 @@ pub fn run(
 fn build_router() -> pavex_matchit::Router<u32> {
     let mut router = pavex_matchit::Router::new();
     router.insert("/", 0u32).unwrap();
-    router.insert("/*path", 1u32).unwrap();
-    router.insert("/about", 2u32).unwrap();
-    router.insert("/api/greet", 3u32).unwrap();
-    router.insert("/api/greet/:name", 4u32).unwrap();
-    router.insert("/api/ping", 5u32).unwrap();
+    router.insert("/_server/greet", 1u32).unwrap();
+    router.insert("/_server/redirect_to", 2u32).unwrap();
+    router.insert("/about", 3u32).unwrap();
+    router.insert("/admin", 4u32).unwrap();
+    router.insert("/admin/*path", 5u32).unwrap();
+    router.insert("/admin/_server/admin/stats", 6u32).unwrap();
+    router.insert("/api/greet/:name", 7u32).unwrap();
+    router.insert("/api/ping", 8u32).unwrap();
+    router.insert("/blocking", 9u32).unwrap();
     router
 }
 async fn route_request(
@@ -54,17 +61,15 @@ async fn route_request(
     let matched_route = match server_state.router.at(&request_head.target.path()) {
         Ok(m) => m,
         Err(_) => {
-            let allowed_methods: pavex::router::AllowedMethods = pavex::router::MethodAllowList::from_iter(
-                    vec![],
-                )
-                .into();
             let matched_route_template = pavex::request::path::MatchedPathPattern::new(
                 "*",
             );
-            return route_6::entrypoint(
-                    &request_head,
+            return route_9::entrypoint(
+                    server_state.application_state.s1.clone(),
+                    request_head,
+                    request_body,
                     matched_route_template,
-                    &allowed_methods,
+                    &server_state.application_state.s0,
                 )
                 .await;
         }
@@ -82,23 +87,22 @@ async fn route_request(
             match &request_head.method {
                 &pavex::http::Method::GET => {
                     route_1::entrypoint(
-                            server_state.application_state.s0.clone(),
-                            request_head,
-                            request_body,
+                            &url_params,
                             server_state.application_state.s1.clone(),
                             matched_route_template,
+                            request_body,
+                            &server_state.application_state.s0,
+                            &request_head,
                         )
                         .await
                 }
                 _ => {
-                    let allowed_methods: pavex::router::AllowedMethods = pavex::router::MethodAllowList::from_iter([
-                            pavex::http::Method::GET,
-                        ])
-                        .into();
-                    route_6::entrypoint(
-                            &request_head,
+                    route_9::entrypoint(
+                            server_state.application_state.s1.clone(),
+                            request_head,
+                            request_body,
                             matched_route_template,
-                            &allowed_methods,
+                            &server_state.application_state.s0,
                         )
                         .await
                 }
@@ -106,94 +110,172 @@ async fn route_request(
         }
         1u32 => {
             let matched_route_template = pavex::request::path::MatchedPathPattern::new(
-                "/*path",
+                "/_server/greet",
             );
             match &request_head.method {
-                &pavex::http::Method::GET => {
-                    route_5::entrypoint(
-                            url_params,
+                &pavex::http::Method::POST => {
+                    route_0::entrypoint(
+                            request_head,
+                            request_body,
                             matched_route_template,
-                            &request_head,
+                            &server_state.application_state.s0,
                         )
                         .await
                 }
                 _ => {
-                    let allowed_methods: pavex::router::AllowedMethods = pavex::router::MethodAllowList::from_iter([
-                            pavex::http::Method::GET,
-                        ])
-                        .into();
-                    route_6::entrypoint(
-                            &request_head,
+                    route_9::entrypoint(
+                            server_state.application_state.s1.clone(),
+                            request_head,
+                            request_body,
                             matched_route_template,
-                            &allowed_methods,
+                            &server_state.application_state.s0,
                         )
                         .await
                 }
             }
         }
         2u32 => {
+            let matched_route_template = pavex::request::path::MatchedPathPattern::new(
+                "/_server/redirect_to",
+            );
+            match &request_head.method {
+                &pavex::http::Method::POST => {
+                    route_10::entrypoint(
+                            request_head,
+                            request_body,
+                            matched_route_template,
+                            &server_state.application_state.s0,
+                        )
+                        .await
+                }
+                _ => {
+                    route_9::entrypoint(
+                            server_state.application_state.s1.clone(),
+                            request_head,
+                            request_body,
+                            matched_route_template,
+                            &server_state.application_state.s0,
+                        )
+                        .await
+                }
+            }
+        }
+        3u32 => {
             let matched_route_template = pavex::request::path::MatchedPathPattern::new(
                 "/about",
             );
             match &request_head.method {
                 &pavex::http::Method::GET => {
                     route_2::entrypoint(
-                            server_state.application_state.s0.clone(),
+                            &url_params,
+                            server_state.application_state.s1.clone(),
+                            matched_route_template,
+                            request_body,
+                            &server_state.application_state.s0,
+                            &request_head,
+                        )
+                        .await
+                }
+                _ => {
+                    route_9::entrypoint(
+                            server_state.application_state.s1.clone(),
+                            request_head,
+                            request_body,
+                            matched_route_template,
+                            &server_state.application_state.s0,
+                        )
+                        .await
+                }
+            }
+        }
+        4u32 => {
+            let matched_route_template = pavex::request::path::MatchedPathPattern::new(
+                "/admin",
+            );
+            match &request_head.method {
+                &pavex::http::Method::GET => {
+                    route_7::entrypoint(
+                            &url_params,
+                            server_state.application_state.s1.clone(),
+                            matched_route_template,
+                            request_body,
+                            &server_state.application_state.s3,
+                            &request_head,
+                        )
+                        .await
+                }
+                _ => {
+                    route_9::entrypoint(
+                            server_state.application_state.s1.clone(),
                             request_head,
                             request_body,
+                            matched_route_template,
+                            &server_state.application_state.s0,
+                        )
+                        .await
+                }
+            }
+        }
+        5u32 => {
+            let matched_route_template = pavex::request::path::MatchedPathPattern::new(
+                "/admin/*path",
+            );
+            match &request_head.method {
+                &pavex::http::Method::GET => {
+                    route_8::entrypoint(
                             server_state.application_state.s1.clone(),
+                            request_head,
+                            request_body,
                             matched_route_template,
+                            &server_state.application_state.s3,
                         )
                         .await
                 }
                 _ => {
-                    let allowed_methods: pavex::router::AllowedMethods = pavex::router::MethodAllowList::from_iter([
-                            pavex::http::Method::GET,
-                        ])
-                        .into();
-                    route_6::entrypoint(
-                            &request_head,
+                    route_9::entrypoint(
+                            server_state.application_state.s1.clone(),
+                            request_head,
+                            request_body,
                             matched_route_template,
-                            &allowed_methods,
+                            &server_state.application_state.s0,
                         )
                         .await
                 }
             }
         }
-        3u32 => {
+        6u32 => {
             let matched_route_template = pavex::request::path::MatchedPathPattern::new(
-                "/api/greet",
+                "/admin/_server/admin/stats",
             );
             match &request_head.method {
                 &pavex::http::Method::POST => {
-                    route_0::entrypoint(
+                    route_6::entrypoint(
                             request_head,
                             request_body,
                             matched_route_template,
+                            &server_state.application_state.s3,
                         )
                         .await
                 }
                 _ => {
-                    let allowed_methods: pavex::router::AllowedMethods = pavex::router::MethodAllowList::from_iter([
-                            pavex::http::Method::POST,
-                        ])
-                        .into();
-                    route_6::entrypoint(
-                            &request_head,
+                    route_9::entrypoint(
+                            server_state.application_state.s1.clone(),
+                            request_head,
+                            request_body,
                             matched_route_template,
-                            &allowed_methods,
+                            &server_state.application_state.s0,
                         )
                         .await
                 }
             }
         }
-        4u32 => {
+        7u32 => {
             let matched_route_template = pavex::request::path::MatchedPathPattern::new(
                 "/api/greet/:name",
             );
             match &request_head.method {
                 &pavex::http::Method::GET => {
-                    route_4::entrypoint(
+                    route_5::entrypoint(
                             url_params,
                             &request_head,
                             matched_route_template,
@@ -202,36 +284,60 @@ async fn route_request(
                         .await
                 }
                 _ => {
-                    let allowed_methods: pavex::router::AllowedMethods = pavex::router::MethodAllowList::from_iter([
-                            pavex::http::Method::GET,
-                        ])
-                        .into();
-                    route_6::entrypoint(
-                            &request_head,
+                    route_9::entrypoint(
+                            server_state.application_state.s1.clone(),
+                            request_head,
+                            request_body,
                             matched_route_template,
-                            &allowed_methods,
+                            &server_state.application_state.s0,
                         )
                         .await
                 }
             }
         }
-        5u32 => {
+        8u32 => {
             let matched_route_template = pavex::request::path::MatchedPathPattern::new(
                 "/api/ping",
             );
             match &request_head.method {
                 &pavex::http::Method::GET => {
-                    route_3::entrypoint(matched_route_template, &request_head).await
+                    route_4::entrypoint(matched_route_template, &request_head).await
                 }
                 _ => {
-                    let allowed_methods: pavex::router::AllowedMethods = pavex::router::MethodAllowList::from_iter([
-                            pavex::http::Method::GET,
-                        ])
-                        .into();
-                    route_6::entrypoint(
+                    route_9::entrypoint(
+                            server_state.application_state.s1.clone(),
+                            request_head,
+                            request_body,
+                            matched_route_template,
+                            &server_state.application_state.s0,
+                        )
+                        .await
+                }
+            }
+        }
+        9u32 => {
+            let matched_route_template = pavex::request::path::MatchedPathPattern::new(
+                "/blocking",
+            );
+            match &request_head.method {
+                &pavex::http::Method::GET => {
+                    route_3::entrypoint(
+                            &url_params,
+                            server_state.application_state.s1.clone(),
+                            matched_route_template,
+                            request_body,
+                            &server_state.application_state.s0,
                             &request_head,
+                        )
+                        .await
+                }
+                _ => {
+                    route_9::entrypoint(
+                            server_state.application_state.s1.clone(),
+                            request_head,
+                            request_body,
                             matched_route_template,
-                            &allowed_methods,
+                            &server_state.application_state.s0,
                         )
                         .await
                 }
@@ -241,71 +347,79 @@ async fn route_request(
     }
 }
 pub mod route_0 {
-    pub async fn entrypoint(
+    pub async fn entrypoint<'a>(
         s_0: pavex::request::RequestHead,
         s_1: pavex::request::body::RawIncomingBody,
         s_2: pavex::request::path::MatchedPathPattern,
+        s_3: &'a leptos_pavex::PavexRouteIndex,
     ) -> pavex::response::Response {
-        let response = wrapping_0(s_0, s_1, s_2).await;
+        let response = wrapping_0(s_0, s_1, s_2, s_3).await;
         response
     }
-    async fn stage_1(
+    async fn stage_1<'a>(
         s_0: pavex::request::RequestHead,
         s_1: pavex::request::body::RawIncomingBody,
-        s_2: pavex::request::path::MatchedPathPattern,
+        s_2: &'a leptos_pavex::PavexRouteIndex,
+        s_3: pavex::request::path::MatchedPathPattern,
     ) -> pavex::response::Response {
-        let response = wrapping_1(s_0, s_1, s_2).await;
+        let response = wrapping_1(s_0, s_1, s_3, s_2).await;
         response
     }
-    async fn stage_2<'a>(
+    async fn stage_2<'a, 'b>(
         s_0: pavex::request::RequestHead,
         s_1: pavex::request::body::RawIncomingBody,
-        s_2: &'a pavex_tracing::RootSpan,
+        s_2: &'a leptos_pavex::PavexRouteIndex,
+        s_3: &'b pavex_tracing::RootSpan,
     ) -> pavex::response::Response {
-        let response = handler(s_0, s_1).await;
-        let response = post_processing_0(response, s_2).await;
+        let response = handler(s_0, s_1, s_2).await;
+        let response = post_processing_0(response, s_3).await;
         response
     }
     async fn wrapping_0(
         v0: pavex::request::RequestHead,
         v1: pavex::request::body::RawIncomingBody,
         v2: pavex::request::path::MatchedPathPattern,
+        v3: &leptos_pavex::PavexRouteIndex,
     ) -> pavex::response::Response {
-        let v3 = crate::route_0::Next0 {
+        let v4 = crate::route_0::Next0 {
             s_0: v0,
             s_1: v1,
-            s_2: v2,
+            s_2: v3,
+            s_3: v2,
             next: stage_1,
         };
-        let v4 = pavex::middleware::Next::new(v3);
-        let v5 = pavex::middleware::wrap_noop(v4).await;
-        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v5)
+        let v5 = pavex::middleware::Next::new(v4);
+        let v6 = pavex::middleware::wrap_noop(v5).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v6)
     }
     async fn wrapping_1(
         v0: pavex::request::RequestHead,
         v1: pavex::request::body::RawIncomingBody,
         v2: pavex::request::path::MatchedPathPattern,
+        v3: &leptos_pavex::PavexRouteIndex,
     ) -> pavex::response::Response {
-        let v3 = pavex::telemetry::ServerRequestId::generate();
-        let v4 = app::telemetry::root_span(&v0, v2, v3);
-        let v5 = crate::route_0::Next1 {
+        let v4 = pavex::telemetry::ServerRequestId::generate();
+        let v5 = app::telemetry::root_span(&v0, v2, v4);
+        let v6 = crate::route_0::Next1 {
             s_0: v0,
             s_1: v1,
-            s_2: &v4,
+            s_2: v3,
+            s_3: &v5,
             next: stage_2,
         };
-        let v6 = pavex::middleware::Next::new(v5);
-        let v7 = <pavex_tracing::RootSpan as core::clone::Clone>::clone(&v4);
-        let v8 = pavex_tracing::logger(v7, v6).await;
-        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v8)
+        let v7 = pavex::middleware::Next::new(v6);
+        let v8 = <pavex_tracing::RootSpan as core::clone::Clone>::clone(&v5);
+        let v9 = pavex_tracing::logger(v8, v7).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v9)
     }
     async fn handler(
         v0: pavex::request::RequestHead,
         v1: pavex::request::body::RawIncomingBody,
+        v2: &leptos_pavex::PavexRouteIndex,
     ) -> pavex::response::Response {
-        let v2 = app::leptos::additional_context_serverfn(&v0);
-        let v3 = leptos_pavex::server_fn::handle_server_fns(v0, v1, v2).await;
-        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v3)
+        let v3 = app::leptos::additional_context_serverfn(&v0);
+        let v4 = leptos_pavex::server_fn::handle_server_fns(v2, v0, v1, v3).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v4)
     }
     async fn post_processing_0(
         v0: pavex::response::Response,
@@ -314,139 +428,152 @@ pub mod route_0 {
         let v2 = app::telemetry::response_logger(v0, v1).await;
         <pavex::response::Response as pavex::response::IntoResponse>::into_response(v2)
     }
-    struct Next0<T>
+    struct Next0<'a, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
         s_0: pavex::request::RequestHead,
         s_1: pavex::request::body::RawIncomingBody,
-        s_2: pavex::request::path::MatchedPathPattern,
+        s_2: &'a leptos_pavex::PavexRouteIndex,
+        s_3: pavex::request::path::MatchedPathPattern,
         next: fn(
             pavex::request::RequestHead,
             pavex::request::body::RawIncomingBody,
+            &'a leptos_pavex::PavexRouteIndex,
             pavex::request::path::MatchedPathPattern,
         ) -> T,
     }
-    impl<T> std::future::IntoFuture for Next0<T>
+    impl<'a, T> std::future::IntoFuture for Next0<'a, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
         type Output = pavex::response::Response;
         type IntoFuture = T;
         fn into_future(self) -> Self::IntoFuture {
-            (self.next)(self.s_0, self.s_1, self.s_2)
+            (self.next)(self.s_0, self.s_1, self.s_2, self.s_3)
         }
     }
-    struct Next1<'a, T>
+    struct Next1<'a, 'b, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
         s_0: pavex::request::RequestHead,
         s_1: pavex::request::body::RawIncomingBody,
-        s_2: &'a pavex_tracing::RootSpan,
+        s_2: &'a leptos_pavex::PavexRouteIndex,
+        s_3: &'b pavex_tracing::RootSpan,
         next: fn(
             pavex::request::RequestHead,
             pavex::request::body::RawIncomingBody,
-            &'a pavex_tracing::RootSpan,
+            &'a leptos_pavex::PavexRouteIndex,
+            &'b pavex_tracing::RootSpan,
         ) -> T,
     }
-    impl<'a, T> std::future::IntoFuture for Next1<'a, T>
+    impl<'a, 'b, T> std::future::IntoFuture for Next1<'a, 'b, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
         type Output = pavex::response::Response;
         type IntoFuture = T;
         fn into_future(self) -> Self::IntoFuture {
-            (self.next)(self.s_0, self.s_1, self.s_2)
+            (self.next)(self.s_0, self.s_1, self.s_2, self.s_3)
         }
     }
 }
 pub mod route_1 {
-    pub async fn entrypoint(
-        s_0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-        s_1: pavex::request::RequestHead,
-        s_2: pavex::request::body::RawIncomingBody,
-        s_3: leptos_config::LeptosOptions,
-        s_4: pavex::request::path::MatchedPathPattern,
+    pub async fn entrypoint<'a, 'b, 'c, 'd, 'e>(
+        s_0: &'c pavex::request::path::RawPathParams<'a, 'b>,
+        s_1: leptos_config::LeptosOptions,
+        s_2: pavex::request::path::MatchedPathPattern,
+        s_3: pavex::request::body::RawIncomingBody,
+        s_4: &'d leptos_pavex::PavexRouteIndex,
+        s_5: &'e pavex::request::RequestHead,
     ) -> pavex::response::Response {
-        let response = wrapping_0(s_0, s_1, s_2, s_3, s_4).await;
+        let response = wrapping_0(s_0, s_1, s_2, s_3, s_4, s_5).await;
         response
     }
-    async fn stage_1(
-        s_0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-        s_1: pavex::request::RequestHead,
-        s_2: pavex::request::body::RawIncomingBody,
-        s_3: leptos_config::LeptosOptions,
-        s_4: pavex::request::path::MatchedPathPattern,
+    async fn stage_1<'a, 'b, 'c, 'd, 'e>(
+        s_0: &'a pavex::request::RequestHead,
+        s_1: leptos_config::LeptosOptions,
+        s_2: pavex::request::path::MatchedPathPattern,
+        s_3: pavex::request::body::RawIncomingBody,
+        s_4: &'d pavex::request::path::RawPathParams<'b, 'c>,
+        s_5: &'e leptos_pavex::PavexRouteIndex,
     ) -> pavex::response::Response {
-        let response = wrapping_1(s_0, s_1, s_2, s_3, s_4).await;
+        let response = wrapping_1(s_4, s_1, s_2, s_3, s_5, s_0).await;
         response
     }
-    async fn stage_2<'a, 'b>(
-        s_0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-        s_1: pavex::request::RequestHead,
-        s_2: pavex::request::body::RawIncomingBody,
-        s_3: leptos_config::LeptosOptions,
-        s_4: &'a pavex::request::path::MatchedPathPattern,
-        s_5: &'b pavex_tracing::RootSpan,
+    async fn stage_2<'a, 'b, 'c, 'd, 'e, 'f, 'g>(
+        s_0: &'a pavex::request::RequestHead,
+        s_1: leptos_config::LeptosOptions,
+        s_2: &'b pavex::request::path::MatchedPathPattern,
+        s_3: pavex::request::body::RawIncomingBody,
+        s_4: &'e pavex::request::path::RawPathParams<'c, 'd>,
+        s_5: &'f leptos_pavex::PavexRouteIndex,
+        s_6: &'g pavex_tracing::RootSpan,
     ) -> pavex::response::Response {
-        let response = handler(s_0, s_1, s_2, s_3, s_4).await;
-        let response = post_processing_0(response, s_5).await;
+        let response = handler(s_0, s_1, s_2, s_3, s_4, s_5).await;
+        let response = post_processing_0(response, s_6).await;
         response
     }
     async fn wrapping_0(
-        v0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-        v1: pavex::request::RequestHead,
-        v2: pavex::request::body::RawIncomingBody,
-        v3: leptos_config::LeptosOptions,
-        v4: pavex::request::path::MatchedPathPattern,
+        v0: &pavex::request::path::RawPathParams<'_, '_>,
+        v1: leptos_config::LeptosOptions,
+        v2: pavex::request::path::MatchedPathPattern,
+        v3: pavex::request::body::RawIncomingBody,
+        v4: &leptos_pavex::PavexRouteIndex,
+        v5: &pavex::request::RequestHead,
     ) -> pavex::response::Response {
-        let v5 = crate::route_1::Next0 {
-            s_0: v0,
+        let v6 = crate::route_1::Next0 {
+            s_0: v5,
             s_1: v1,
             s_2: v2,
             s_3: v3,
-            s_4: v4,
+            s_4: v0,
+            s_5: v4,
             next: stage_1,
         };
-        let v6 = pavex::middleware::Next::new(v5);
-        let v7 = pavex::middleware::wrap_noop(v6).await;
-        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v7)
+        let v7 = pavex::middleware::Next::new(v6);
+        let v8 = pavex::middleware::wrap_noop(v7).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v8)
     }
     async fn wrapping_1(
-        v0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-        v1: pavex::request::RequestHead,
-        v2: pavex::request::body::RawIncomingBody,
-        v3: leptos_config::LeptosOptions,
-        v4: pavex::request::path::MatchedPathPattern,
+        v0: &pavex::request::path::RawPathParams<'_, '_>,
+        v1: leptos_config::LeptosOptions,
+        v2: pavex::request::path::MatchedPathPattern,
+        v3: pavex::request::body::RawIncomingBody,
+        v4: &leptos_pavex::PavexRouteIndex,
+        v5: &pavex::request::RequestHead,
     ) -> pavex::response::Response {
-        let v5 = pavex::telemetry::ServerRequestId::generate();
-        let v6 = app::telemetry::root_span(&v1, v4, v5);
-        let v7 = crate::route_1::Next1 {
-            s_0: v0,
+        let v6 = pavex::telemetry::ServerRequestId::generate();
+        let v7 = app::telemetry::root_span(v5, v2, v6);
+        let v8 = crate::route_1::Next1 {
+            s_0: v5,
             s_1: v1,
-            s_2: v2,
+            s_2: &v2,
             s_3: v3,
-            s_4: &v4,
-            s_5: &v6,
+            s_4: v0,
+            s_5: v4,
+            s_6: &v7,
             next: stage_2,
         };
-        let v8 = pavex::middleware::Next::new(v7);
-        let v9 = <pavex_tracing::RootSpan as core::clone::Clone>::clone(&v6);
-        let v10 = pavex_tracing::logger(v9, v8).await;
-        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v10)
+        let v9 = pavex::middleware::Next::new(v8);
+        let v10 = <pavex_tracing::RootSpan as core::clone::Clone>::clone(&v7);
+        let v11 = pavex_tracing::logger(v10, v9).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v11)
     }
     async fn handler(
-        v0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-        v1: pavex::request::RequestHead,
-        v2: pavex::request::body::RawIncomingBody,
-        v3: leptos_config::LeptosOptions,
-        v4: &pavex::request::path::MatchedPathPattern,
+        v0: &pavex::request::RequestHead,
+        v1: leptos_config::LeptosOptions,
+        v2: &pavex::request::path::MatchedPathPattern,
+        v3: pavex::request::body::RawIncomingBody,
+        v4: &pavex::request::path::RawPathParams<'_, '_>,
+        v5: &leptos_pavex::PavexRouteIndex,
     ) -> pavex::response::Response {
-        let v5 = app::leptos::additional_context_components(&v1);
-        let v6 = app::leptos::generate_app(&v5, v3, &v1);
-        let v7 = leptos_pavex::render_route(v0, v1, v2, v4, v5, v6).await;
-        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v7)
+        let v6 = app::leptos::additional_context_components(v0);
+        let v7 = <leptos_config::LeptosOptions as core::clone::Clone>::clone(&v1);
+        let v8 = app::leptos::generate_app(&v6, v7, v0);
+        let v9 = leptos_pavex::render_route(v5, &v1, v0, v3, v2, v4, v6, v8).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v9)
     }
     async fn post_processing_0(
         v0: pavex::response::Response,
@@ -455,149 +582,172 @@ pub mod route_1 {
         let v2 = app::telemetry::response_logger(v0, v1).await;
         <pavex::response::Response as pavex::response::IntoResponse>::into_response(v2)
     }
-    struct Next0<T>
+    struct Next0<'a, 'b, 'c, 'd, 'e, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
-        s_0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-        s_1: pavex::request::RequestHead,
-        s_2: pavex::request::body::RawIncomingBody,
-        s_3: leptos_config::LeptosOptions,
-        s_4: pavex::request::path::MatchedPathPattern,
+        s_0: &'a pavex::request::RequestHead,
+        s_1: leptos_config::LeptosOptions,
+        s_2: pavex::request::path::MatchedPathPattern,
+        s_3: pavex::request::body::RawIncomingBody,
+        s_4: &'d pavex::request::path::RawPathParams<'b, 'c>,
+        s_5: &'e leptos_pavex::PavexRouteIndex,
         next: fn(
-            alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-            pavex::request::RequestHead,
-            pavex::request::body::RawIncomingBody,
+            &'a pavex::request::RequestHead,
             leptos_config::LeptosOptions,
             pavex::request::path::MatchedPathPattern,
+            pavex::request::body::RawIncomingBody,
+            &'d pavex::request::path::RawPathParams<'b, 'c>,
+            &'e leptos_pavex::PavexRouteIndex,
         ) -> T,
     }
-    impl<T> std::future::IntoFuture for Next0<T>
+    impl<'a, 'b, 'c, 'd, 'e, T> std::future::IntoFuture for Next0<'a, 'b, 'c, 'd, 'e, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
         type Output = pavex::response::Response;
         type IntoFuture = T;
         fn into_future(self) -> Self::IntoFuture {
-            (self.next)(self.s_0, self.s_1, self.s_2, self.s_3, self.s_4)
+            (self.next)(self.s_0, self.s_1, self.s_2, self.s_3, self.s_4, self.s_5)
         }
     }
-    struct Next1<'a, 'b, T>
+    struct Next1<'a, 'b, 'c, 'd, 'e, 'f, 'g, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
-        s_0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-        s_1: pavex::request::RequestHead,
-        s_2: pavex::request::body::RawIncomingBody,
-        s_3: leptos_config::LeptosOptions,
-        s_4: &'a pavex::request::path::MatchedPathPattern,
-        s_5: &'b pavex_tracing::RootSpan,
+        s_0: &'a pavex::request::RequestHead,
+        s_1: leptos_config::LeptosOptions,
+        s_2: &'b pavex::request::path::MatchedPathPattern,
+        s_3: pavex::request::body::RawIncomingBody,
+        s_4: &'e pavex::request::path::RawPathParams<'c, 'd>,
+        s_5: &'f leptos_pavex::PavexRouteIndex,
+        s_6: &'g pavex_tracing::RootSpan,
         next: fn(
-            alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-            pavex::request::RequestHead,
-            pavex::request::body::RawIncomingBody,
+            &'a pavex::request::RequestHead,
             leptos_config::LeptosOptions,
-            &'a pavex::request::path::MatchedPathPattern,
-            &'b pavex_tracing::RootSpan,
+            &'b pavex::request::path::MatchedPathPattern,
+            pavex::request::body::RawIncomingBody,
+            &'e pavex::request::path::RawPathParams<'c, 'd>,
+            &'f leptos_pavex::PavexRouteIndex,
+            &'g pavex_tracing::RootSpan,
         ) -> T,
     }
-    impl<'a, 'b, T> std::future::IntoFuture for Next1<'a, 'b, T>
+    impl<'a, 'b, 'c, 'd, 'e, 'f, 'g, T> std::future::IntoFuture
+    for Next1<'a, 'b, 'c, 'd, 'e, 'f, 'g, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
         type Output = pavex::response::Response;
         type IntoFuture = T;
         fn into_future(self) -> Self::IntoFuture {
-            (self.next)(self.s_0, self.s_1, self.s_2, self.s_3, self.s_4, self.s_5)
+            (self
+                .next)(
+                self.s_0,
+                self.s_1,
+                self.s_2,
+                self.s_3,
+                self.s_4,
+                self.s_5,
+                self.s_6,
+            )
         }
     }
 }
 pub mod route_2 {
-    pub async fn entrypoint(
-        s_0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-        s_1: pavex::request::RequestHead,
-        s_2: pavex::request::body::RawIncomingBody,
-        s_3: leptos_config::LeptosOptions,
-        s_4: pavex::request::path::MatchedPathPattern,
+    pub async fn entrypoint<'a, 'b, 'c, 'd, 'e>(
+        s_0: &'c pavex::request::path::RawPathParams<'a, 'b>,
+        s_1: leptos_config::LeptosOptions,
+        s_2: pavex::request::path::MatchedPathPattern,
+        s_3: pavex::request::body::RawIncomingBody,
+        s_4: &'d leptos_pavex::PavexRouteIndex,
+        s_5: &'e pavex::request::RequestHead,
     ) -> pavex::response::Response {
-        let response = wrapping_0(s_0, s_1, s_2, s_3, s_4).await;
+        let response = wrapping_0(s_0, s_1, s_2, s_3, s_4, s_5).await;
         response
     }
-    async fn stage_1(
-        s_0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-        s_1: pavex::request::RequestHead,
-        s_2: pavex::request::body::RawIncomingBody,
-        s_3: leptos_config::LeptosOptions,
-        s_4: pavex::request::path::MatchedPathPattern,
+    async fn stage_1<'a, 'b, 'c, 'd, 'e>(
+        s_0: &'a pavex::request::RequestHead,
+        s_1: leptos_config::LeptosOptions,
+        s_2: pavex::request::path::MatchedPathPattern,
+        s_3: pavex::request::body::RawIncomingBody,
+        s_4: &'d pavex::request::path::RawPathParams<'b, 'c>,
+        s_5: &'e leptos_pavex::PavexRouteIndex,
     ) -> pavex::response::Response {
-        let response = wrapping_1(s_0, s_1, s_2, s_3, s_4).await;
+        let response = wrapping_1(s_4, s_1, s_2, s_3, s_5, s_0).await;
         response
     }
-    async fn stage_2<'a, 'b>(
-        s_0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-        s_1: pavex::request::RequestHead,
-        s_2: pavex::request::body::RawIncomingBody,
-        s_3: leptos_config::LeptosOptions,
-        s_4: &'a pavex::request::path::MatchedPathPattern,
-        s_5: &'b pavex_tracing::RootSpan,
+    async fn stage_2<'a, 'b, 'c, 'd, 'e, 'f, 'g>(
+        s_0: &'a pavex::request::RequestHead,
+        s_1: leptos_config::LeptosOptions,
+        s_2: &'b pavex::request::path::MatchedPathPattern,
+        s_3: pavex::request::body::RawIncomingBody,
+        s_4: &'e pavex::request::path::RawPathParams<'c, 'd>,
+        s_5: &'f leptos_pavex::PavexRouteIndex,
+        s_6: &'g pavex_tracing::RootSpan,
     ) -> pavex::response::Response {
-        let response = handler(s_0, s_1, s_2, s_3, s_4).await;
-        let response = post_processing_0(response, s_5).await;
+        let response = handler(s_0, s_1, s_2, s_3, s_4, s_5).await;
+        let response = post_processing_0(response, s_6).await;
         response
     }
     async fn wrapping_0(
-        v0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-        v1: pavex::request::RequestHead,
-        v2: pavex::request::body::RawIncomingBody,
-        v3: leptos_config::LeptosOptions,
-        v4: pavex::request::path::MatchedPathPattern,
+        v0: &pavex::request::path::RawPathParams<'_, '_>,
+        v1: leptos_config::LeptosOptions,
+        v2: pavex::request::path::MatchedPathPattern,
+        v3: pavex::request::body::RawIncomingBody,
+        v4: &leptos_pavex::PavexRouteIndex,
+        v5: &pavex::request::RequestHead,
     ) -> pavex::response::Response {
-        let v5 = crate::route_2::Next0 {
-            s_0: v0,
+        let v6 = crate::route_2::Next0 {
+            s_0: v5,
             s_1: v1,
             s_2: v2,
             s_3: v3,
-            s_4: v4,
+            s_4: v0,
+            s_5: v4,
             next: stage_1,
         };
-        let v6 = pavex::middleware::Next::new(v5);
-        let v7 = pavex::middleware::wrap_noop(v6).await;
-        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v7)
+        let v7 = pavex::middleware::Next::new(v6);
+        let v8 = pavex::middleware::wrap_noop(v7).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v8)
     }
     async fn wrapping_1(
-        v0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-        v1: pavex::request::RequestHead,
-        v2: pavex::request::body::RawIncomingBody,
-        v3: leptos_config::LeptosOptions,
-        v4: pavex::request::path::MatchedPathPattern,
+        v0: &pavex::request::path::RawPathParams<'_, '_>,
+        v1: leptos_config::LeptosOptions,
+        v2: pavex::request::path::MatchedPathPattern,
+        v3: pavex::request::body::RawIncomingBody,
+        v4: &leptos_pavex::PavexRouteIndex,
+        v5: &pavex::request::RequestHead,
     ) -> pavex::response::Response {
-        let v5 = pavex::telemetry::ServerRequestId::generate();
-        let v6 = app::telemetry::root_span(&v1, v4, v5);
-        let v7 = crate::route_2::Next1 {
-            s_0: v0,
+        let v6 = pavex::telemetry::ServerRequestId::generate();
+        let v7 = app::telemetry::root_span(v5, v2, v6);
+        let v8 = crate::route_2::Next1 {
+            s_0: v5,
             s_1: v1,
-            s_2: v2,
+            s_2: &v2,
             s_3: v3,
-            s_4: &v4,
-            s_5: &v6,
+            s_4: v0,
+            s_5: v4,
+            s_6: &v7,
             next: stage_2,
         };
-        let v8 = pavex::middleware::Next::new(v7);
-        let v9 = <pavex_tracing::RootSpan as core::clone::Clone>::clone(&v6);
-        let v10 = pavex_tracing::logger(v9, v8).await;
-        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v10)
+        let v9 = pavex::middleware::Next::new(v8);
+        let v10 = <pavex_tracing::RootSpan as core::clone::Clone>::clone(&v7);
+        let v11 = pavex_tracing::logger(v10, v9).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v11)
     }
     async fn handler(
-        v0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-        v1: pavex::request::RequestHead,
-        v2: pavex::request::body::RawIncomingBody,
-        v3: leptos_config::LeptosOptions,
-        v4: &pavex::request::path::MatchedPathPattern,
+        v0: &pavex::request::RequestHead,
+        v1: leptos_config::LeptosOptions,
+        v2: &pavex::request::path::MatchedPathPattern,
+        v3: pavex::request::body::RawIncomingBody,
+        v4: &pavex::request::path::RawPathParams<'_, '_>,
+        v5: &leptos_pavex::PavexRouteIndex,
     ) -> pavex::response::Response {
-        let v5 = app::leptos::additional_context_components(&v1);
-        let v6 = app::leptos::generate_app(&v5, v3, &v1);
-        let v7 = leptos_pavex::render_route(v0, v1, v2, v4, v5, v6).await;
-        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v7)
+        let v6 = app::leptos::additional_context_components(v0);
+        let v7 = <leptos_config::LeptosOptions as core::clone::Clone>::clone(&v1);
+        let v8 = app::leptos::generate_app(&v6, v7, v0);
+        let v9 = leptos_pavex::render_route(v5, &v1, v0, v3, v2, v4, v6, v8).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v9)
     }
     async fn post_processing_0(
         v0: pavex::response::Response,
@@ -606,53 +756,200 @@ pub mod route_2 {
         let v2 = app::telemetry::response_logger(v0, v1).await;
         <pavex::response::Response as pavex::response::IntoResponse>::into_response(v2)
     }
-    struct Next0<T>
+    struct Next0<'a, 'b, 'c, 'd, 'e, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
-        s_0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-        s_1: pavex::request::RequestHead,
-        s_2: pavex::request::body::RawIncomingBody,
-        s_3: leptos_config::LeptosOptions,
-        s_4: pavex::request::path::MatchedPathPattern,
+        s_0: &'a pavex::request::RequestHead,
+        s_1: leptos_config::LeptosOptions,
+        s_2: pavex::request::path::MatchedPathPattern,
+        s_3: pavex::request::body::RawIncomingBody,
+        s_4: &'d pavex::request::path::RawPathParams<'b, 'c>,
+        s_5: &'e leptos_pavex::PavexRouteIndex,
         next: fn(
-            alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-            pavex::request::RequestHead,
-            pavex::request::body::RawIncomingBody,
+            &'a pavex::request::RequestHead,
             leptos_config::LeptosOptions,
             pavex::request::path::MatchedPathPattern,
+            pavex::request::body::RawIncomingBody,
+            &'d pavex::request::path::RawPathParams<'b, 'c>,
+            &'e leptos_pavex::PavexRouteIndex,
         ) -> T,
     }
-    impl<T> std::future::IntoFuture for Next0<T>
+    impl<'a, 'b, 'c, 'd, 'e, T> std::future::IntoFuture for Next0<'a, 'b, 'c, 'd, 'e, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
         type Output = pavex::response::Response;
         type IntoFuture = T;
         fn into_future(self) -> Self::IntoFuture {
-            (self.next)(self.s_0, self.s_1, self.s_2, self.s_3, self.s_4)
+            (self.next)(self.s_0, self.s_1, self.s_2, self.s_3, self.s_4, self.s_5)
         }
     }
-    struct Next1<'a, 'b, T>
+    struct Next1<'a, 'b, 'c, 'd, 'e, 'f, 'g, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
-        s_0: alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-        s_1: pavex::request::RequestHead,
-        s_2: pavex::request::body::RawIncomingBody,
-        s_3: leptos_config::LeptosOptions,
-        s_4: &'a pavex::request::path::MatchedPathPattern,
-        s_5: &'b pavex_tracing::RootSpan,
+        s_0: &'a pavex::request::RequestHead,
+        s_1: leptos_config::LeptosOptions,
+        s_2: &'b pavex::request::path::MatchedPathPattern,
+        s_3: pavex::request::body::RawIncomingBody,
+        s_4: &'e pavex::request::path::RawPathParams<'c, 'd>,
+        s_5: &'f leptos_pavex::PavexRouteIndex,
+        s_6: &'g pavex_tracing::RootSpan,
         next: fn(
-            alloc::vec::Vec<leptos_pavex::PavexRouteListing>,
-            pavex::request::RequestHead,
+            &'a pavex::request::RequestHead,
+            leptos_config::LeptosOptions,
+            &'b pavex::request::path::MatchedPathPattern,
             pavex::request::body::RawIncomingBody,
+            &'e pavex::request::path::RawPathParams<'c, 'd>,
+            &'f leptos_pavex::PavexRouteIndex,
+            &'g pavex_tracing::RootSpan,
+        ) -> T,
+    }
+    impl<'a, 'b, 'c, 'd, 'e, 'f, 'g, T> std::future::IntoFuture
+    for Next1<'a, 'b, 'c, 'd, 'e, 'f, 'g, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        type Output = pavex::response::Response;
+        type IntoFuture = T;
+        fn into_future(self) -> Self::IntoFuture {
+            (self
+                .next)(
+                self.s_0,
+                self.s_1,
+                self.s_2,
+                self.s_3,
+                self.s_4,
+                self.s_5,
+                self.s_6,
+            )
+        }
+    }
+}
+pub mod route_3 {
+    pub async fn entrypoint<'a, 'b, 'c, 'd, 'e>(
+        s_0: &'c pavex::request::path::RawPathParams<'a, 'b>,
+        s_1: leptos_config::LeptosOptions,
+        s_2: pavex::request::path::MatchedPathPattern,
+        s_3: pavex::request::body::RawIncomingBody,
+        s_4: &'d leptos_pavex::PavexRouteIndex,
+        s_5: &'e pavex::request::RequestHead,
+    ) -> pavex::response::Response {
+        let response = wrapping_0(s_0, s_1, s_2, s_3, s_4, s_5).await;
+        response
+    }
+    async fn stage_1<'a, 'b, 'c, 'd, 'e>(
+        s_0: &'a pavex::request::RequestHead,
+        s_1: leptos_config::LeptosOptions,
+        s_2: pavex::request::path::MatchedPathPattern,
+        s_3: pavex::request::body::RawIncomingBody,
+        s_4: &'d pavex::request::path::RawPathParams<'b, 'c>,
+        s_5: &'e leptos_pavex::PavexRouteIndex,
+    ) -> pavex::response::Response {
+        let response = wrapping_1(s_4, s_1, s_2, s_3, s_5, s_0).await;
+        response
+    }
+    async fn stage_2<'a, 'b, 'c, 'd, 'e, 'f, 'g>(
+        s_0: &'a pavex::request::RequestHead,
+        s_1: leptos_config::LeptosOptions,
+        s_2: &'b pavex::request::path::MatchedPathPattern,
+        s_3: pavex::request::body::RawIncomingBody,
+        s_4: &'e pavex::request::path::RawPathParams<'c, 'd>,
+        s_5: &'f leptos_pavex::PavexRouteIndex,
+        s_6: &'g pavex_tracing::RootSpan,
+    ) -> pavex::response::Response {
+        let response = handler(s_0, s_1, s_2, s_3, s_4, s_5).await;
+        let response = post_processing_0(response, s_6).await;
+        response
+    }
+    async fn wrapping_0(
+        v0: &pavex::request::path::RawPathParams<'_, '_>,
+        v1: leptos_config::LeptosOptions,
+        v2: pavex::request::path::MatchedPathPattern,
+        v3: pavex::request::body::RawIncomingBody,
+        v4: &leptos_pavex::PavexRouteIndex,
+        v5: &pavex::request::RequestHead,
+    ) -> pavex::response::Response {
+        let v6 = crate::route_3::Next0 {
+            s_0: v5,
+            s_1: v1,
+            s_2: v2,
+            s_3: v3,
+            s_4: v0,
+            s_5: v4,
+            next: stage_1,
+        };
+        let v7 = pavex::middleware::Next::new(v6);
+        let v8 = pavex::middleware::wrap_noop(v7).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v8)
+    }
+    async fn wrapping_1(
+        v0: &pavex::request::path::RawPathParams<'_, '_>,
+        v1: leptos_config::LeptosOptions,
+        v2: pavex::request::path::MatchedPathPattern,
+        v3: pavex::request::body::RawIncomingBody,
+        v4: &leptos_pavex::PavexRouteIndex,
+        v5: &pavex::request::RequestHead,
+    ) -> pavex::response::Response {
+        let v6 = pavex::telemetry::ServerRequestId::generate();
+        let v7 = app::telemetry::root_span(v5, v2, v6);
+        let v8 = crate::route_3::Next1 {
+            s_0: v5,
+            s_1: v1,
+            s_2: &v2,
+            s_3: v3,
+            s_4: v0,
+            s_5: v4,
+            s_6: &v7,
+            next: stage_2,
+        };
+        let v9 = pavex::middleware::Next::new(v8);
+        let v10 = <pavex_tracing::RootSpan as core::clone::Clone>::clone(&v7);
+        let v11 = pavex_tracing::logger(v10, v9).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v11)
+    }
+    async fn handler(
+        v0: &pavex::request::RequestHead,
+        v1: leptos_config::LeptosOptions,
+        v2: &pavex::request::path::MatchedPathPattern,
+        v3: pavex::request::body::RawIncomingBody,
+        v4: &pavex::request::path::RawPathParams<'_, '_>,
+        v5: &leptos_pavex::PavexRouteIndex,
+    ) -> pavex::response::Response {
+        let v6 = app::leptos::additional_context_components(v0);
+        let v7 = <leptos_config::LeptosOptions as core::clone::Clone>::clone(&v1);
+        let v8 = app::leptos::generate_app(&v6, v7, v0);
+        let v9 = leptos_pavex::render_route(v5, &v1, v0, v3, v2, v4, v6, v8).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v9)
+    }
+    async fn post_processing_0(
+        v0: pavex::response::Response,
+        v1: &pavex_tracing::RootSpan,
+    ) -> pavex::response::Response {
+        let v2 = app::telemetry::response_logger(v0, v1).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v2)
+    }
+    struct Next0<'a, 'b, 'c, 'd, 'e, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        s_0: &'a pavex::request::RequestHead,
+        s_1: leptos_config::LeptosOptions,
+        s_2: pavex::request::path::MatchedPathPattern,
+        s_3: pavex::request::body::RawIncomingBody,
+        s_4: &'d pavex::request::path::RawPathParams<'b, 'c>,
+        s_5: &'e leptos_pavex::PavexRouteIndex,
+        next: fn(
+            &'a pavex::request::RequestHead,
             leptos_config::LeptosOptions,
-            &'a pavex::request::path::MatchedPathPattern,
-            &'b pavex_tracing::RootSpan,
+            pavex::request::path::MatchedPathPattern,
+            pavex::request::body::RawIncomingBody,
+            &'d pavex::request::path::RawPathParams<'b, 'c>,
+            &'e leptos_pavex::PavexRouteIndex,
         ) -> T,
     }
-    impl<'a, 'b, T> std::future::IntoFuture for Next1<'a, 'b, T>
+    impl<'a, 'b, 'c, 'd, 'e, T> std::future::IntoFuture for Next0<'a, 'b, 'c, 'd, 'e, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
@@ -662,8 +959,49 @@ pub mod route_2 {
             (self.next)(self.s_0, self.s_1, self.s_2, self.s_3, self.s_4, self.s_5)
         }
     }
+    struct Next1<'a, 'b, 'c, 'd, 'e, 'f, 'g, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        s_0: &'a pavex::request::RequestHead,
+        s_1: leptos_config::LeptosOptions,
+        s_2: &'b pavex::request::path::MatchedPathPattern,
+        s_3: pavex::request::body::RawIncomingBody,
+        s_4: &'e pavex::request::path::RawPathParams<'c, 'd>,
+        s_5: &'f leptos_pavex::PavexRouteIndex,
+        s_6: &'g pavex_tracing::RootSpan,
+        next: fn(
+            &'a pavex::request::RequestHead,
+            leptos_config::LeptosOptions,
+            &'b pavex::request::path::MatchedPathPattern,
+            pavex::request::body::RawIncomingBody,
+            &'e pavex::request::path::RawPathParams<'c, 'd>,
+            &'f leptos_pavex::PavexRouteIndex,
+            &'g pavex_tracing::RootSpan,
+        ) -> T,
+    }
+    impl<'a, 'b, 'c, 'd, 'e, 'f, 'g, T> std::future::IntoFuture
+    for Next1<'a, 'b, 'c, 'd, 'e, 'f, 'g, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        type Output = pavex::response::Response;
+        type IntoFuture = T;
+        fn into_future(self) -> Self::IntoFuture {
+            (self
+                .next)(
+                self.s_0,
+                self.s_1,
+                self.s_2,
+                self.s_3,
+                self.s_4,
+                self.s_5,
+                self.s_6,
+            )
+        }
+    }
 }
-pub mod route_3 {
+pub mod route_4 {
     pub async fn entrypoint<'a>(
         s_0: pavex::request::path::MatchedPathPattern,
         s_1: &'a pavex::request::RequestHead,
@@ -687,7 +1025,7 @@ pub mod route_3 {
         v0: pavex::request::path::MatchedPathPattern,
         v1: &pavex::request::RequestHead,
     ) -> pavex::response::Response {
-        let v2 = crate::route_3::Next0 {
+        let v2 = crate::route_4::Next0 {
             s_0: v0,
             s_1: v1,
             next: stage_1,
@@ -702,7 +1040,7 @@ pub mod route_3 {
     ) -> pavex::response::Response {
         let v2 = pavex::telemetry::ServerRequestId::generate();
         let v3 = app::telemetry::root_span(v1, v0, v2);
-        let v4 = crate::route_3::Next1 {
+        let v4 = crate::route_4::Next1 {
             s_0: &v3,
             next: stage_2,
         };
@@ -761,7 +1099,7 @@ pub mod route_3 {
         }
     }
 }
-pub mod route_4 {
+pub mod route_5 {
     pub async fn entrypoint<'a, 'b, 'c, 'd>(
         s_0: pavex::request::path::RawPathParams<'a, 'b>,
         s_1: &'c pavex::request::RequestHead,
@@ -795,7 +1133,7 @@ pub mod route_4 {
         v2: pavex::request::path::MatchedPathPattern,
         v3: &app::configuration::AppConfig,
     ) -> pavex::response::Response {
-        let v4 = crate::route_4::Next0 {
+        let v4 = crate::route_5::Next0 {
             s_0: v0,
             s_1: v3,
             s_2: v2,
@@ -814,7 +1152,7 @@ pub mod route_4 {
     ) -> pavex::response::Response {
         let v4 = pavex::telemetry::ServerRequestId::generate();
         let v5 = app::telemetry::root_span(v1, v2, v4);
-        let v6 = crate::route_4::Next1 {
+        let v6 = crate::route_5::Next1 {
             s_0: &v5,
             s_1: v0,
             s_2: v3,
@@ -908,84 +1246,79 @@ pub mod route_4 {
         }
     }
 }
-pub mod route_5 {
-    pub async fn entrypoint<'a, 'b, 'c>(
-        s_0: pavex::request::path::RawPathParams<'a, 'b>,
-        s_1: pavex::request::path::MatchedPathPattern,
-        s_2: &'c pavex::request::RequestHead,
+pub mod route_6 {
+    pub async fn entrypoint<'a>(
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: pavex::request::path::MatchedPathPattern,
+        s_3: &'a app::admin::AdminRouteIndex,
     ) -> pavex::response::Response {
-        let response = wrapping_0(s_0, s_1, s_2).await;
+        let response = wrapping_0(s_0, s_1, s_2, s_3).await;
         response
     }
-    async fn stage_1<'a, 'b, 'c>(
-        s_0: pavex::request::path::RawPathParams<'a, 'b>,
-        s_1: pavex::request::path::MatchedPathPattern,
-        s_2: &'c pavex::request::RequestHead,
+    async fn stage_1<'a>(
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: &'a app::admin::AdminRouteIndex,
+        s_3: pavex::request::path::MatchedPathPattern,
     ) -> pavex::response::Response {
-        let response = wrapping_1(s_0, s_1, s_2).await;
+        let response = wrapping_1(s_0, s_1, s_3, s_2).await;
         response
     }
-    async fn stage_2<'a, 'b, 'c>(
-        s_0: pavex::request::path::RawPathParams<'a, 'b>,
-        s_1: &'c pavex_tracing::RootSpan,
+    async fn stage_2<'a, 'b>(
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: &'a app::admin::AdminRouteIndex,
+        s_3: &'b pavex_tracing::RootSpan,
     ) -> pavex::response::Response {
-        let response = handler(s_0, s_1).await;
-        let response = post_processing_0(response, s_1).await;
+        let response = handler(s_0, s_1, s_2).await;
+        let response = post_processing_0(response, s_3).await;
         response
     }
     async fn wrapping_0(
-        v0: pavex::request::path::RawPathParams<'_, '_>,
-        v1: pavex::request::path::MatchedPathPattern,
-        v2: &pavex::request::RequestHead,
+        v0: pavex::request::RequestHead,
+        v1: pavex::request::body::RawIncomingBody,
+        v2: pavex::request::path::MatchedPathPattern,
+        v3: &app::admin::AdminRouteIndex,
     ) -> pavex::response::Response {
-        let v3 = crate::route_5::Next0 {
+        let v4 = crate::route_6::Next0 {
             s_0: v0,
             s_1: v1,
-            s_2: v2,
+            s_2: v3,
+            s_3: v2,
             next: stage_1,
         };
-        let v4 = pavex::middleware::Next::new(v3);
-        let v5 = pavex::middleware::wrap_noop(v4).await;
-        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v5)
+        let v5 = pavex::middleware::Next::new(v4);
+        let v6 = pavex::middleware::wrap_noop(v5).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v6)
     }
     async fn wrapping_1(
-        v0: pavex::request::path::RawPathParams<'_, '_>,
-        v1: pavex::request::path::MatchedPathPattern,
-        v2: &pavex::request::RequestHead,
+        v0: pavex::request::RequestHead,
+        v1: pavex::request::body::RawIncomingBody,
+        v2: pavex::request::path::MatchedPathPattern,
+        v3: &app::admin::AdminRouteIndex,
     ) -> pavex::response::Response {
-        let v3 = pavex::telemetry::ServerRequestId::generate();
-        let v4 = app::telemetry::root_span(v2, v1, v3);
-        let v5 = crate::route_5::Next1 {
+        let v4 = pavex::telemetry::ServerRequestId::generate();
+        let v5 = app::telemetry::root_span(&v0, v2, v4);
+        let v6 = crate::route_6::Next1 {
             s_0: v0,
-            s_1: &v4,
+            s_1: v1,
+            s_2: v3,
+            s_3: &v5,
             next: stage_2,
         };
-        let v6 = pavex::middleware::Next::new(v5);
-        let v7 = <pavex_tracing::RootSpan as core::clone::Clone>::clone(&v4);
-        let v8 = pavex_tracing::logger(v7, v6).await;
-        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v8)
+        let v7 = pavex::middleware::Next::new(v6);
+        let v8 = <pavex_tracing::RootSpan as core::clone::Clone>::clone(&v5);
+        let v9 = pavex_tracing::logger(v8, v7).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v9)
     }
     async fn handler(
-        v0: pavex::request::path::RawPathParams<'_, '_>,
-        v1: &pavex_tracing::RootSpan,
+        v0: pavex::request::RequestHead,
+        v1: pavex::request::body::RawIncomingBody,
+        v2: &app::admin::AdminRouteIndex,
     ) -> pavex::response::Response {
-        let v2 = pavex::request::path::PathParams::extract(v0);
-        let v3 = match v2 {
-            Ok(ok) => ok,
-            Err(v3) => {
-                return {
-                    let v4 = pavex::request::path::errors::ExtractPathParamsError::into_response(
-                        &v3,
-                    );
-                    let v5 = pavex::Error::new(v3);
-                    app::telemetry::error_logger(&v5, v1).await;
-                    <pavex::response::Response as pavex::response::IntoResponse>::into_response(
-                        v4,
-                    )
-                };
-            }
-        };
-        let v4 = leptos_pavex::file_helpers::serve_files(&v3);
+        let v3 = app::leptos::additional_context_serverfn(&v0);
+        let v4 = app::admin::handle_server_fns(v2, v0, v1, v3).await;
         <pavex::response::Response as pavex::response::IntoResponse>::into_response(v4)
     }
     async fn post_processing_0(
@@ -995,111 +1328,152 @@ pub mod route_5 {
         let v2 = app::telemetry::response_logger(v0, v1).await;
         <pavex::response::Response as pavex::response::IntoResponse>::into_response(v2)
     }
-    struct Next0<'a, 'b, 'c, T>
+    struct Next0<'a, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
-        s_0: pavex::request::path::RawPathParams<'a, 'b>,
-        s_1: pavex::request::path::MatchedPathPattern,
-        s_2: &'c pavex::request::RequestHead,
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: &'a app::admin::AdminRouteIndex,
+        s_3: pavex::request::path::MatchedPathPattern,
         next: fn(
-            pavex::request::path::RawPathParams<'a, 'b>,
+            pavex::request::RequestHead,
+            pavex::request::body::RawIncomingBody,
+            &'a app::admin::AdminRouteIndex,
             pavex::request::path::MatchedPathPattern,
-            &'c pavex::request::RequestHead,
         ) -> T,
     }
-    impl<'a, 'b, 'c, T> std::future::IntoFuture for Next0<'a, 'b, 'c, T>
+    impl<'a, T> std::future::IntoFuture for Next0<'a, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
         type Output = pavex::response::Response;
         type IntoFuture = T;
         fn into_future(self) -> Self::IntoFuture {
-            (self.next)(self.s_0, self.s_1, self.s_2)
+            (self.next)(self.s_0, self.s_1, self.s_2, self.s_3)
         }
     }
-    struct Next1<'a, 'b, 'c, T>
+    struct Next1<'a, 'b, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
-        s_0: pavex::request::path::RawPathParams<'a, 'b>,
-        s_1: &'c pavex_tracing::RootSpan,
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: &'a app::admin::AdminRouteIndex,
+        s_3: &'b pavex_tracing::RootSpan,
         next: fn(
-            pavex::request::path::RawPathParams<'a, 'b>,
-            &'c pavex_tracing::RootSpan,
+            pavex::request::RequestHead,
+            pavex::request::body::RawIncomingBody,
+            &'a app::admin::AdminRouteIndex,
+            &'b pavex_tracing::RootSpan,
         ) -> T,
     }
-    impl<'a, 'b, 'c, T> std::future::IntoFuture for Next1<'a, 'b, 'c, T>
+    impl<'a, 'b, T> std::future::IntoFuture for Next1<'a, 'b, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
         type Output = pavex::response::Response;
         type IntoFuture = T;
         fn into_future(self) -> Self::IntoFuture {
-            (self.next)(self.s_0, self.s_1)
+            (self.next)(self.s_0, self.s_1, self.s_2, self.s_3)
         }
     }
 }
-pub mod route_6 {
-    pub async fn entrypoint<'a, 'b>(
-        s_0: &'a pavex::request::RequestHead,
-        s_1: pavex::request::path::MatchedPathPattern,
-        s_2: &'b pavex::router::AllowedMethods,
+pub mod route_7 {
+    pub async fn entrypoint<'a, 'b, 'c, 'd, 'e>(
+        s_0: &'c pavex::request::path::RawPathParams<'a, 'b>,
+        s_1: leptos_config::LeptosOptions,
+        s_2: pavex::request::path::MatchedPathPattern,
+        s_3: pavex::request::body::RawIncomingBody,
+        s_4: &'d app::admin::AdminRouteIndex,
+        s_5: &'e pavex::request::RequestHead,
     ) -> pavex::response::Response {
-        let response = wrapping_0(s_0, s_1, s_2).await;
+        let response = wrapping_0(s_0, s_1, s_2, s_3, s_4, s_5).await;
         response
     }
-    async fn stage_1<'a, 'b>(
-        s_0: &'a pavex::router::AllowedMethods,
-        s_1: pavex::request::path::MatchedPathPattern,
-        s_2: &'b pavex::request::RequestHead,
+    async fn stage_1<'a, 'b, 'c, 'd, 'e>(
+        s_0: &'a pavex::request::RequestHead,
+        s_1: leptos_config::LeptosOptions,
+        s_2: pavex::request::path::MatchedPathPattern,
+        s_3: pavex::request::body::RawIncomingBody,
+        s_4: &'d pavex::request::path::RawPathParams<'b, 'c>,
+        s_5: &'e app::admin::AdminRouteIndex,
     ) -> pavex::response::Response {
-        let response = wrapping_1(s_2, s_1, s_0).await;
+        let response = wrapping_1(s_4, s_1, s_2, s_3, s_5, s_0).await;
         response
     }
-    async fn stage_2<'a, 'b>(
-        s_0: &'a pavex::router::AllowedMethods,
-        s_1: &'b pavex_tracing::RootSpan,
+    async fn stage_2<'a, 'b, 'c, 'd, 'e, 'f, 'g>(
+        s_0: &'a pavex::request::RequestHead,
+        s_1: leptos_config::LeptosOptions,
+        s_2: &'b pavex::request::path::MatchedPathPattern,
+        s_3: pavex::request::body::RawIncomingBody,
+        s_4: &'e pavex::request::path::RawPathParams<'c, 'd>,
+        s_5: &'f app::admin::AdminRouteIndex,
+        s_6: &'g pavex_tracing::RootSpan,
     ) -> pavex::response::Response {
-        let response = handler(s_0).await;
-        let response = post_processing_0(response, s_1).await;
+        let response = handler(s_0, s_1, s_2, s_3, s_4, s_5).await;
+        let response = post_processing_0(response, s_6).await;
         response
     }
     async fn wrapping_0(
-        v0: &pavex::request::RequestHead,
-        v1: pavex::request::path::MatchedPathPattern,
-        v2: &pavex::router::AllowedMethods,
+        v0: &pavex::request::path::RawPathParams<'_, '_>,
+        v1: leptos_config::LeptosOptions,
+        v2: pavex::request::path::MatchedPathPattern,
+        v3: pavex::request::body::RawIncomingBody,
+        v4: &app::admin::AdminRouteIndex,
+        v5: &pavex::request::RequestHead,
     ) -> pavex::response::Response {
-        let v3 = crate::route_6::Next0 {
-            s_0: v2,
+        let v6 = crate::route_7::Next0 {
+            s_0: v5,
             s_1: v1,
-            s_2: v0,
+            s_2: v2,
+            s_3: v3,
+            s_4: v0,
+            s_5: v4,
             next: stage_1,
         };
-        let v4 = pavex::middleware::Next::new(v3);
-        let v5 = pavex::middleware::wrap_noop(v4).await;
-        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v5)
+        let v7 = pavex::middleware::Next::new(v6);
+        let v8 = pavex::middleware::wrap_noop(v7).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v8)
     }
     async fn wrapping_1(
-        v0: &pavex::request::RequestHead,
-        v1: pavex::request::path::MatchedPathPattern,
-        v2: &pavex::router::AllowedMethods,
-    ) -> pavex::response::Response {
-        let v3 = pavex::telemetry::ServerRequestId::generate();
-        let v4 = app::telemetry::root_span(v0, v1, v3);
-        let v5 = crate::route_6::Next1 {
-            s_0: v2,
-            s_1: &v4,
+        v0: &pavex::request::path::RawPathParams<'_, '_>,
+        v1: leptos_config::LeptosOptions,
+        v2: pavex::request::path::MatchedPathPattern,
+        v3: pavex::request::body::RawIncomingBody,
+        v4: &app::admin::AdminRouteIndex,
+        v5: &pavex::request::RequestHead,
+    ) -> pavex::response::Response {
+        let v6 = pavex::telemetry::ServerRequestId::generate();
+        let v7 = app::telemetry::root_span(v5, v2, v6);
+        let v8 = crate::route_7::Next1 {
+            s_0: v5,
+            s_1: v1,
+            s_2: &v2,
+            s_3: v3,
+            s_4: v0,
+            s_5: v4,
+            s_6: &v7,
             next: stage_2,
         };
-        let v6 = pavex::middleware::Next::new(v5);
-        let v7 = <pavex_tracing::RootSpan as core::clone::Clone>::clone(&v4);
-        let v8 = pavex_tracing::logger(v7, v6).await;
-        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v8)
+        let v9 = pavex::middleware::Next::new(v8);
+        let v10 = <pavex_tracing::RootSpan as core::clone::Clone>::clone(&v7);
+        let v11 = pavex_tracing::logger(v10, v9).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v11)
     }
-    async fn handler(v0: &pavex::router::AllowedMethods) -> pavex::response::Response {
-        let v1 = pavex::router::default_fallback(v0).await;
-        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v1)
+    async fn handler(
+        v0: &pavex::request::RequestHead,
+        v1: leptos_config::LeptosOptions,
+        v2: &pavex::request::path::MatchedPathPattern,
+        v3: pavex::request::body::RawIncomingBody,
+        v4: &pavex::request::path::RawPathParams<'_, '_>,
+        v5: &app::admin::AdminRouteIndex,
+    ) -> pavex::response::Response {
+        let v6 = app::leptos::additional_context_components(v0);
+        let v7 = <leptos_config::LeptosOptions as core::clone::Clone>::clone(&v1);
+        let v8 = app::admin::generate_admin_app(&v6, v7, v0);
+        let v9 = app::admin::render_route(v5, &v1, v0, v3, v2, v4, v6, v8).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v9)
     }
     async fn post_processing_0(
         v0: pavex::response::Response,
@@ -1108,45 +1482,560 @@ pub mod route_6 {
         let v2 = app::telemetry::response_logger(v0, v1).await;
         <pavex::response::Response as pavex::response::IntoResponse>::into_response(v2)
     }
-    struct Next0<'a, 'b, T>
+    struct Next0<'a, 'b, 'c, 'd, 'e, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
-        s_0: &'a pavex::router::AllowedMethods,
-        s_1: pavex::request::path::MatchedPathPattern,
-        s_2: &'b pavex::request::RequestHead,
+        s_0: &'a pavex::request::RequestHead,
+        s_1: leptos_config::LeptosOptions,
+        s_2: pavex::request::path::MatchedPathPattern,
+        s_3: pavex::request::body::RawIncomingBody,
+        s_4: &'d pavex::request::path::RawPathParams<'b, 'c>,
+        s_5: &'e app::admin::AdminRouteIndex,
         next: fn(
-            &'a pavex::router::AllowedMethods,
+            &'a pavex::request::RequestHead,
+            leptos_config::LeptosOptions,
             pavex::request::path::MatchedPathPattern,
-            &'b pavex::request::RequestHead,
+            pavex::request::body::RawIncomingBody,
+            &'d pavex::request::path::RawPathParams<'b, 'c>,
+            &'e app::admin::AdminRouteIndex,
         ) -> T,
     }
-    impl<'a, 'b, T> std::future::IntoFuture for Next0<'a, 'b, T>
+    impl<'a, 'b, 'c, 'd, 'e, T> std::future::IntoFuture for Next0<'a, 'b, 'c, 'd, 'e, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
         type Output = pavex::response::Response;
         type IntoFuture = T;
         fn into_future(self) -> Self::IntoFuture {
-            (self.next)(self.s_0, self.s_1, self.s_2)
+            (self.next)(self.s_0, self.s_1, self.s_2, self.s_3, self.s_4, self.s_5)
         }
     }
-    struct Next1<'a, 'b, T>
+    struct Next1<'a, 'b, 'c, 'd, 'e, 'f, 'g, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
-        s_0: &'a pavex::router::AllowedMethods,
-        s_1: &'b pavex_tracing::RootSpan,
-        next: fn(&'a pavex::router::AllowedMethods, &'b pavex_tracing::RootSpan) -> T,
+        s_0: &'a pavex::request::RequestHead,
+        s_1: leptos_config::LeptosOptions,
+        s_2: &'b pavex::request::path::MatchedPathPattern,
+        s_3: pavex::request::body::RawIncomingBody,
+        s_4: &'e pavex::request::path::RawPathParams<'c, 'd>,
+        s_5: &'f app::admin::AdminRouteIndex,
+        s_6: &'g pavex_tracing::RootSpan,
+        next: fn(
+            &'a pavex::request::RequestHead,
+            leptos_config::LeptosOptions,
+            &'b pavex::request::path::MatchedPathPattern,
+            pavex::request::body::RawIncomingBody,
+            &'e pavex::request::path::RawPathParams<'c, 'd>,
+            &'f app::admin::AdminRouteIndex,
+            &'g pavex_tracing::RootSpan,
+        ) -> T,
     }
-    impl<'a, 'b, T> std::future::IntoFuture for Next1<'a, 'b, T>
+    impl<'a, 'b, 'c, 'd, 'e, 'f, 'g, T> std::future::IntoFuture
+    for Next1<'a, 'b, 'c, 'd, 'e, 'f, 'g, T>
     where
         T: std::future::Future<Output = pavex::response::Response>,
     {
         type Output = pavex::response::Response;
         type IntoFuture = T;
         fn into_future(self) -> Self::IntoFuture {
-            (self.next)(self.s_0, self.s_1)
+            (self
+                .next)(
+                self.s_0,
+                self.s_1,
+                self.s_2,
+                self.s_3,
+                self.s_4,
+                self.s_5,
+                self.s_6,
+            )
+        }
+    }
+}
+pub mod route_8 {
+    pub async fn entrypoint<'a>(
+        s_0: leptos_config::LeptosOptions,
+        s_1: pavex::request::RequestHead,
+        s_2: pavex::request::body::RawIncomingBody,
+        s_3: pavex::request::path::MatchedPathPattern,
+        s_4: &'a app::admin::AdminRouteIndex,
+    ) -> pavex::response::Response {
+        let response = wrapping_0(s_0, s_1, s_2, s_3, s_4).await;
+        response
+    }
+    async fn stage_1<'a>(
+        s_0: leptos_config::LeptosOptions,
+        s_1: pavex::request::RequestHead,
+        s_2: pavex::request::body::RawIncomingBody,
+        s_3: &'a app::admin::AdminRouteIndex,
+        s_4: pavex::request::path::MatchedPathPattern,
+    ) -> pavex::response::Response {
+        let response = wrapping_1(s_0, s_1, s_2, s_4, s_3).await;
+        response
+    }
+    async fn stage_2<'a, 'b>(
+        s_0: leptos_config::LeptosOptions,
+        s_1: pavex::request::RequestHead,
+        s_2: pavex::request::body::RawIncomingBody,
+        s_3: &'a app::admin::AdminRouteIndex,
+        s_4: &'b pavex_tracing::RootSpan,
+    ) -> pavex::response::Response {
+        let response = handler(s_0, s_1, s_2, s_3).await;
+        let response = post_processing_0(response, s_4).await;
+        response
+    }
+    async fn wrapping_0(
+        v0: leptos_config::LeptosOptions,
+        v1: pavex::request::RequestHead,
+        v2: pavex::request::body::RawIncomingBody,
+        v3: pavex::request::path::MatchedPathPattern,
+        v4: &app::admin::AdminRouteIndex,
+    ) -> pavex::response::Response {
+        let v5 = crate::route_8::Next0 {
+            s_0: v0,
+            s_1: v1,
+            s_2: v2,
+            s_3: v4,
+            s_4: v3,
+            next: stage_1,
+        };
+        let v6 = pavex::middleware::Next::new(v5);
+        let v7 = pavex::middleware::wrap_noop(v6).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v7)
+    }
+    async fn wrapping_1(
+        v0: leptos_config::LeptosOptions,
+        v1: pavex::request::RequestHead,
+        v2: pavex::request::body::RawIncomingBody,
+        v3: pavex::request::path::MatchedPathPattern,
+        v4: &app::admin::AdminRouteIndex,
+    ) -> pavex::response::Response {
+        let v5 = pavex::telemetry::ServerRequestId::generate();
+        let v6 = app::telemetry::root_span(&v1, v3, v5);
+        let v7 = crate::route_8::Next1 {
+            s_0: v0,
+            s_1: v1,
+            s_2: v2,
+            s_3: v4,
+            s_4: &v6,
+            next: stage_2,
+        };
+        let v8 = pavex::middleware::Next::new(v7);
+        let v9 = <pavex_tracing::RootSpan as core::clone::Clone>::clone(&v6);
+        let v10 = pavex_tracing::logger(v9, v8).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v10)
+    }
+    async fn handler(
+        v0: leptos_config::LeptosOptions,
+        v1: pavex::request::RequestHead,
+        v2: pavex::request::body::RawIncomingBody,
+        v3: &app::admin::AdminRouteIndex,
+    ) -> pavex::response::Response {
+        let v4 = app::leptos::additional_context_components(&v1);
+        let v5 = <leptos_config::LeptosOptions as core::clone::Clone>::clone(&v0);
+        let v6 = app::admin::generate_admin_app(&v4, v5, &v1);
+        let v7 = app::admin::file_and_error_handler(v3, &v0, v1, v2, v4, v6).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v7)
+    }
+    async fn post_processing_0(
+        v0: pavex::response::Response,
+        v1: &pavex_tracing::RootSpan,
+    ) -> pavex::response::Response {
+        let v2 = app::telemetry::response_logger(v0, v1).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v2)
+    }
+    struct Next0<'a, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        s_0: leptos_config::LeptosOptions,
+        s_1: pavex::request::RequestHead,
+        s_2: pavex::request::body::RawIncomingBody,
+        s_3: &'a app::admin::AdminRouteIndex,
+        s_4: pavex::request::path::MatchedPathPattern,
+        next: fn(
+            leptos_config::LeptosOptions,
+            pavex::request::RequestHead,
+            pavex::request::body::RawIncomingBody,
+            &'a app::admin::AdminRouteIndex,
+            pavex::request::path::MatchedPathPattern,
+        ) -> T,
+    }
+    impl<'a, T> std::future::IntoFuture for Next0<'a, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        type Output = pavex::response::Response;
+        type IntoFuture = T;
+        fn into_future(self) -> Self::IntoFuture {
+            (self.next)(self.s_0, self.s_1, self.s_2, self.s_3, self.s_4)
+        }
+    }
+    struct Next1<'a, 'b, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        s_0: leptos_config::LeptosOptions,
+        s_1: pavex::request::RequestHead,
+        s_2: pavex::request::body::RawIncomingBody,
+        s_3: &'a app::admin::AdminRouteIndex,
+        s_4: &'b pavex_tracing::RootSpan,
+        next: fn(
+            leptos_config::LeptosOptions,
+            pavex::request::RequestHead,
+            pavex::request::body::RawIncomingBody,
+            &'a app::admin::AdminRouteIndex,
+            &'b pavex_tracing::RootSpan,
+        ) -> T,
+    }
+    impl<'a, 'b, T> std::future::IntoFuture for Next1<'a, 'b, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        type Output = pavex::response::Response;
+        type IntoFuture = T;
+        fn into_future(self) -> Self::IntoFuture {
+            (self.next)(self.s_0, self.s_1, self.s_2, self.s_3, self.s_4)
+        }
+    }
+}
+pub mod route_9 {
+    pub async fn entrypoint<'a>(
+        s_0: leptos_config::LeptosOptions,
+        s_1: pavex::request::RequestHead,
+        s_2: pavex::request::body::RawIncomingBody,
+        s_3: pavex::request::path::MatchedPathPattern,
+        s_4: &'a leptos_pavex::PavexRouteIndex,
+    ) -> pavex::response::Response {
+        let response = wrapping_0(s_0, s_1, s_2, s_3, s_4).await;
+        response
+    }
+    async fn stage_1<'a>(
+        s_0: leptos_config::LeptosOptions,
+        s_1: pavex::request::RequestHead,
+        s_2: pavex::request::body::RawIncomingBody,
+        s_3: &'a leptos_pavex::PavexRouteIndex,
+        s_4: pavex::request::path::MatchedPathPattern,
+    ) -> pavex::response::Response {
+        let response = wrapping_1(s_0, s_1, s_2, s_4, s_3).await;
+        response
+    }
+    async fn stage_2<'a, 'b>(
+        s_0: leptos_config::LeptosOptions,
+        s_1: pavex::request::RequestHead,
+        s_2: pavex::request::body::RawIncomingBody,
+        s_3: &'a leptos_pavex::PavexRouteIndex,
+        s_4: &'b pavex_tracing::RootSpan,
+    ) -> pavex::response::Response {
+        let response = handler(s_0, s_1, s_2, s_3).await;
+        let response = post_processing_0(response, s_4).await;
+        response
+    }
+    async fn wrapping_0(
+        v0: leptos_config::LeptosOptions,
+        v1: pavex::request::RequestHead,
+        v2: pavex::request::body::RawIncomingBody,
+        v3: pavex::request::path::MatchedPathPattern,
+        v4: &leptos_pavex::PavexRouteIndex,
+    ) -> pavex::response::Response {
+        let v5 = crate::route_9::Next0 {
+            s_0: v0,
+            s_1: v1,
+            s_2: v2,
+            s_3: v4,
+            s_4: v3,
+            next: stage_1,
+        };
+        let v6 = pavex::middleware::Next::new(v5);
+        let v7 = pavex::middleware::wrap_noop(v6).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v7)
+    }
+    async fn wrapping_1(
+        v0: leptos_config::LeptosOptions,
+        v1: pavex::request::RequestHead,
+        v2: pavex::request::body::RawIncomingBody,
+        v3: pavex::request::path::MatchedPathPattern,
+        v4: &leptos_pavex::PavexRouteIndex,
+    ) -> pavex::response::Response {
+        let v5 = pavex::telemetry::ServerRequestId::generate();
+        let v6 = app::telemetry::root_span(&v1, v3, v5);
+        let v7 = crate::route_9::Next1 {
+            s_0: v0,
+            s_1: v1,
+            s_2: v2,
+            s_3: v4,
+            s_4: &v6,
+            next: stage_2,
+        };
+        let v8 = pavex::middleware::Next::new(v7);
+        let v9 = <pavex_tracing::RootSpan as core::clone::Clone>::clone(&v6);
+        let v10 = pavex_tracing::logger(v9, v8).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v10)
+    }
+    async fn handler(
+        v0: leptos_config::LeptosOptions,
+        v1: pavex::request::RequestHead,
+        v2: pavex::request::body::RawIncomingBody,
+        v3: &leptos_pavex::PavexRouteIndex,
+    ) -> pavex::response::Response {
+        let v4 = app::leptos::additional_context_components(&v1);
+        let v5 = <leptos_config::LeptosOptions as core::clone::Clone>::clone(&v0);
+        let v6 = app::leptos::generate_app(&v4, v5, &v1);
+        let v7 = leptos_pavex::file_helpers::file_and_error_handler(
+                v3,
+                &v0,
+                v1,
+                v2,
+                v4,
+                v6,
+            )
+            .await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v7)
+    }
+    async fn post_processing_0(
+        v0: pavex::response::Response,
+        v1: &pavex_tracing::RootSpan,
+    ) -> pavex::response::Response {
+        let v2 = app::telemetry::response_logger(v0, v1).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v2)
+    }
+    struct Next0<'a, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        s_0: leptos_config::LeptosOptions,
+        s_1: pavex::request::RequestHead,
+        s_2: pavex::request::body::RawIncomingBody,
+        s_3: &'a leptos_pavex::PavexRouteIndex,
+        s_4: pavex::request::path::MatchedPathPattern,
+        next: fn(
+            leptos_config::LeptosOptions,
+            pavex::request::RequestHead,
+            pavex::request::body::RawIncomingBody,
+            &'a leptos_pavex::PavexRouteIndex,
+            pavex::request::path::MatchedPathPattern,
+        ) -> T,
+    }
+    impl<'a, T> std::future::IntoFuture for Next0<'a, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        type Output = pavex::response::Response;
+        type IntoFuture = T;
+        fn into_future(self) -> Self::IntoFuture {
+            (self.next)(self.s_0, self.s_1, self.s_2, self.s_3, self.s_4)
+        }
+    }
+    struct Next1<'a, 'b, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        s_0: leptos_config::LeptosOptions,
+        s_1: pavex::request::RequestHead,
+        s_2: pavex::request::body::RawIncomingBody,
+        s_3: &'a leptos_pavex::PavexRouteIndex,
+        s_4: &'b pavex_tracing::RootSpan,
+        next: fn(
+            leptos_config::LeptosOptions,
+            pavex::request::RequestHead,
+            pavex::request::body::RawIncomingBody,
+            &'a leptos_pavex::PavexRouteIndex,
+            &'b pavex_tracing::RootSpan,
+        ) -> T,
+    }
+    impl<'a, 'b, T> std::future::IntoFuture for Next1<'a, 'b, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        type Output = pavex::response::Response;
+        type IntoFuture = T;
+        fn into_future(self) -> Self::IntoFuture {
+            (self.next)(self.s_0, self.s_1, self.s_2, self.s_3, self.s_4)
+        }
+    }
+}
+pub mod route_10 {
+    pub async fn entrypoint<'a>(
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: pavex::request::path::MatchedPathPattern,
+        s_3: &'a leptos_pavex::PavexRouteIndex,
+    ) -> pavex::response::Response {
+        let response = wrapping_0(s_0, s_1, s_2, s_3).await;
+        response
+    }
+    async fn stage_1<'a>(
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: &'a leptos_pavex::PavexRouteIndex,
+        s_3: pavex::request::path::MatchedPathPattern,
+    ) -> pavex::response::Response {
+        let response = wrapping_1(s_0, s_1, s_3, s_2).await;
+        response
+    }
+    async fn stage_2<'a, 'b>(
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: &'a leptos_pavex::PavexRouteIndex,
+        s_3: pavex::request::path::MatchedPathPattern,
+        s_4: &'b pavex_tracing::RootSpan,
+    ) -> pavex::response::Response {
+        let response = wrapping_2(s_3, s_0, s_1, s_2).await;
+        let response = post_processing_0(response, s_4).await;
+        response
+    }
+    async fn stage_3<'a>(
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: &'a leptos_pavex::PavexRouteIndex,
+    ) -> pavex::response::Response {
+        let response = handler(s_0, s_1, s_2).await;
+        response
+    }
+    async fn wrapping_0(
+        v0: pavex::request::RequestHead,
+        v1: pavex::request::body::RawIncomingBody,
+        v2: pavex::request::path::MatchedPathPattern,
+        v3: &leptos_pavex::PavexRouteIndex,
+    ) -> pavex::response::Response {
+        let v4 = crate::route_10::Next0 {
+            s_0: v0,
+            s_1: v1,
+            s_2: v3,
+            s_3: v2,
+            next: stage_1,
+        };
+        let v5 = pavex::middleware::Next::new(v4);
+        let v6 = pavex::middleware::wrap_noop(v5).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v6)
+    }
+    async fn wrapping_1(
+        v0: pavex::request::RequestHead,
+        v1: pavex::request::body::RawIncomingBody,
+        v2: pavex::request::path::MatchedPathPattern,
+        v3: &leptos_pavex::PavexRouteIndex,
+    ) -> pavex::response::Response {
+        let v4 = pavex::telemetry::ServerRequestId::generate();
+        let v5 = app::telemetry::root_span(&v0, v2, v4);
+        let v6 = crate::route_10::Next1 {
+            s_0: v0,
+            s_1: v1,
+            s_2: v3,
+            s_3: v2,
+            s_4: &v5,
+            next: stage_2,
+        };
+        let v7 = pavex::middleware::Next::new(v6);
+        let v8 = <pavex_tracing::RootSpan as core::clone::Clone>::clone(&v5);
+        let v9 = pavex_tracing::logger(v8, v7).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v9)
+    }
+    async fn wrapping_2(
+        v0: pavex::request::path::MatchedPathPattern,
+        v1: pavex::request::RequestHead,
+        v2: pavex::request::body::RawIncomingBody,
+        v3: &leptos_pavex::PavexRouteIndex,
+    ) -> pavex::response::Response {
+        let v4 = crate::route_10::Next2 {
+            s_0: v1,
+            s_1: v2,
+            s_2: v3,
+            next: stage_3,
+        };
+        let v5 = pavex::middleware::Next::new(v4);
+        let v6 = app::routes::server_fns::log_redirects(v5, v0).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v6)
+    }
+    async fn post_processing_0(
+        v0: pavex::response::Response,
+        v1: &pavex_tracing::RootSpan,
+    ) -> pavex::response::Response {
+        let v2 = app::telemetry::response_logger(v0, v1).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v2)
+    }
+    async fn handler(
+        v0: pavex::request::RequestHead,
+        v1: pavex::request::body::RawIncomingBody,
+        v2: &leptos_pavex::PavexRouteIndex,
+    ) -> pavex::response::Response {
+        let v3 = app::leptos::additional_context_serverfn(&v0);
+        let v4 = leptos_pavex::server_fn::handle_server_fns(v2, v0, v1, v3).await;
+        <pavex::response::Response as pavex::response::IntoResponse>::into_response(v4)
+    }
+    struct Next0<'a, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: &'a leptos_pavex::PavexRouteIndex,
+        s_3: pavex::request::path::MatchedPathPattern,
+        next: fn(
+            pavex::request::RequestHead,
+            pavex::request::body::RawIncomingBody,
+            &'a leptos_pavex::PavexRouteIndex,
+            pavex::request::path::MatchedPathPattern,
+        ) -> T,
+    }
+    impl<'a, T> std::future::IntoFuture for Next0<'a, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        type Output = pavex::response::Response;
+        type IntoFuture = T;
+        fn into_future(self) -> Self::IntoFuture {
+            (self.next)(self.s_0, self.s_1, self.s_2, self.s_3)
+        }
+    }
+    struct Next1<'a, 'b, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: &'a leptos_pavex::PavexRouteIndex,
+        s_3: pavex::request::path::MatchedPathPattern,
+        s_4: &'b pavex_tracing::RootSpan,
+        next: fn(
+            pavex::request::RequestHead,
+            pavex::request::body::RawIncomingBody,
+            &'a leptos_pavex::PavexRouteIndex,
+            pavex::request::path::MatchedPathPattern,
+            &'b pavex_tracing::RootSpan,
+        ) -> T,
+    }
+    impl<'a, 'b, T> std::future::IntoFuture for Next1<'a, 'b, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        type Output = pavex::response::Response;
+        type IntoFuture = T;
+        fn into_future(self) -> Self::IntoFuture {
+            (self.next)(self.s_0, self.s_1, self.s_2, self.s_3, self.s_4)
+        }
+    }
+    struct Next2<'a, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        s_0: pavex::request::RequestHead,
+        s_1: pavex::request::body::RawIncomingBody,
+        s_2: &'a leptos_pavex::PavexRouteIndex,
+        next: fn(
+            pavex::request::RequestHead,
+            pavex::request::body::RawIncomingBody,
+            &'a leptos_pavex::PavexRouteIndex,
+        ) -> T,
+    }
+    impl<'a, T> std::future::IntoFuture for Next2<'a, T>
+    where
+        T: std::future::Future<Output = pavex::response::Response>,
+    {
+        type Output = pavex::response::Response;
+        type IntoFuture = T;
+        fn into_future(self) -> Self::IntoFuture {
+            (self.next)(self.s_0, self.s_1, self.s_2)
         }
     }
 }
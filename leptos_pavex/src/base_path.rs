@@ -0,0 +1,183 @@
+use leptos::config::LeptosOptions;
+use std::fmt;
+
+/// The path prefix an app is mounted under, e.g. `/shop`. Every part of the app has to be told
+/// about it:
+///
+/// - Declare the app's routes under it, e.g. `(StaticSegment("shop"), StaticSegment("about"))`. The
+///   `base` of `<Router/>` isn't used to generate or match routes in the Leptos we build on, and
+///   the router gets the whole request path on the server, as it does in the browser.
+///   [`generate_leptos_routes_with_options`](crate::leptos_routes::generate_leptos_routes_with_options)
+///   panics on routes that aren't under it. Still set it as the `base` of `<Router/>`, so the
+///   client only takes over the links within the app.
+/// - Give `<HydrationScripts/>` the options from [`BasePath::hydration_options`], so the JS and
+///   WASM are loaded from under it.
+/// - Call `leptos::server_fn::client::set_server_url` with it in your `hydrate` function, so server
+///   functions are called under it.
+/// - Set it as [`LeptosRoutesOptions::base_path`](crate::leptos_routes::LeptosRoutesOptions), so
+///   server functions are registered under it.
+/// - Attach it to the route index with
+///   [`PavexRouteIndex::with_base_path`](crate::PavexRouteIndex::with_base_path), so server
+///   functions and the assets served by
+///   [`file_and_error_handler`](crate::file_helpers::file_and_error_handler) are found under it.
+///
+/// Keep it in a constant your app crate shares between the server and the client, so they can't
+/// disagree.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BasePath(String);
+
+impl BasePath {
+    /// Slashes around the path don't matter, `shop`, `/shop` and `/shop/` are the same base path.
+    /// An empty path, or `/`, mounts the app at the root.
+    pub fn new(path: &str) -> Self {
+        let path = path.trim_matches('/');
+        if path.is_empty() {
+            Self(String::new())
+        } else {
+            Self(format!("/{path}"))
+        }
+    }
+
+    /// The prefix, with a leading slash and no trailing one, or an empty string for the root.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Put the base path in front of an app path.
+    pub fn prefix(&self, path: &str) -> String {
+        if self.0.is_empty() {
+            path.to_string()
+        } else if path == "/" {
+            self.0.clone()
+        } else {
+            format!("{}{path}", self.0)
+        }
+    }
+
+    /// The options with `site_pkg_dir` under the base path, for `<HydrationScripts/>`. The assets
+    /// stay where they are on disk.
+    pub fn hydration_options(&self, mut options: LeptosOptions) -> LeptosOptions {
+        let pkg_dir = format!("/{}", options.site_pkg_dir.trim_start_matches('/'));
+        options.site_pkg_dir = self.prefix(&pkg_dir).trim_start_matches('/').to_string();
+        options
+    }
+
+    /// Take the base path off the front of a request path, so it can be matched against the app's
+    /// own paths. Returns `None` for paths outside of the base path.
+    pub fn strip(&self, path: &str) -> Option<String> {
+        if self.0.is_empty() {
+            return Some(path.to_string());
+        }
+        match path.strip_prefix(self.0.as_str())? {
+            "" => Some("/".to_string()),
+            rest if rest.starts_with('/') => Some(rest.to_string()),
+            rest if rest.starts_with('?') => Some(format!("/{rest}")),
+            _ => None,
+        }
+    }
+
+    /// Whether the path is the base path or under it.
+    pub fn contains(&self, path: &str) -> bool {
+        self.strip(path).is_some()
+    }
+}
+
+impl From<&str> for BasePath {
+    fn from(path: &str) -> Self {
+        BasePath::new(path)
+    }
+}
+
+impl From<String> for BasePath {
+    fn from(path: String) -> Self {
+        BasePath::new(&path)
+    }
+}
+
+impl fmt::Display for BasePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pavex_helpers::AppFunction;
+    use crate::static_routes::static_request_head;
+    use crate::{generate_route_list, pass_leptos_context, RouteType};
+    use leptos::prelude::*;
+    use leptos_router::components::{Route, Router, Routes};
+    use leptos_router::StaticSegment;
+
+    #[test]
+    fn slashes_around_the_path_are_ignored() {
+        assert_eq!(BasePath::new("shop").as_str(), "/shop");
+        assert_eq!(BasePath::new("/shop/").as_str(), "/shop");
+        assert_eq!(BasePath::new("/").as_str(), "");
+    }
+
+    #[test]
+    fn paths_are_prefixed() {
+        let base = BasePath::new("/shop");
+        assert_eq!(base.prefix("/"), "/shop");
+        assert_eq!(base.prefix("/api/greet"), "/shop/api/greet");
+        assert_eq!(BasePath::default().prefix("/api/greet"), "/api/greet");
+    }
+
+    #[test]
+    fn only_paths_under_the_base_path_are_stripped() {
+        let base = BasePath::new("/shop");
+        assert_eq!(base.strip("/shop").as_deref(), Some("/"));
+        assert_eq!(
+            base.strip("/shop/pkg/app.js").as_deref(),
+            Some("/pkg/app.js")
+        );
+        assert_eq!(base.strip("/shop?page=2").as_deref(), Some("/?page=2"));
+        assert_eq!(base.strip("/shopping"), None);
+        assert_eq!(base.strip("/"), None);
+        assert!(BasePath::default().contains("/anything"));
+    }
+
+    #[test]
+    fn the_pkg_dir_is_put_under_the_base_path() {
+        let options = LeptosOptions::builder().output_name("app").build();
+        let shop = BasePath::new("/shop").hydration_options(options.clone());
+        assert_eq!(shop.site_pkg_dir, "shop/pkg");
+        let root = BasePath::default().hydration_options(options);
+        assert_eq!(root.site_pkg_dir, "pkg");
+    }
+
+    #[test]
+    fn routes_declared_under_the_base_path_are_generated_under_it() {
+        let req_head = static_request_head("/shop/about").unwrap();
+        let owner = pass_leptos_context(&RouteType::Component, &req_head, || {});
+        let app = owner.with(|| {
+            view! {
+                <Router base="/shop">
+                    <Routes fallback=|| "Not found">
+                        <Route
+                            path=(StaticSegment("shop"), StaticSegment("about"))
+                            view=|| view! { <h1>"About"</h1> }
+                        />
+                    </Routes>
+                </Router>
+            }
+            .into_any()
+        });
+        let routes = generate_route_list(AppFunction::new(app));
+        let paths = routes
+            .iter()
+            .map(|listing| listing.path())
+            .collect::<Vec<_>>();
+        assert_eq!(paths, ["/shop/about"]);
+    }
+
+    #[test]
+    fn the_client_is_loaded_from_under_the_base_path() {
+        let options = LeptosOptions::builder().output_name("app").build();
+        let options = BasePath::new("/shop").hydration_options(options);
+        let html = Owner::new().with(|| view! { <HydrationScripts options/> }.to_html());
+        assert!(html.contains("/shop/pkg/app.js"), "{html}");
+    }
+}
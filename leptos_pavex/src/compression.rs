@@ -0,0 +1,367 @@
+use bytes::Bytes;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use http_body::Frame;
+use pavex::http::header::{ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_LENGTH, VARY};
+use pavex::http::{HeaderMap, HeaderValue};
+use pavex::response::body::raw::RawBody;
+use pavex::response::Response;
+use pin_project::pin_project;
+use std::error::Error;
+use std::io::{self, Write};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A compression algorithm we can encode response bodies with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContentEncoding {
+    Gzip,
+    Brotli,
+    Zstd,
+}
+
+impl ContentEncoding {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            ContentEncoding::Gzip => "gzip",
+            ContentEncoding::Brotli => "br",
+            ContentEncoding::Zstd => "zstd",
+        }
+    }
+}
+
+/// Settings for compressing rendered pages and server function responses.
+#[derive(Clone, Debug)]
+pub struct CompressionOptions {
+    /// The encodings we offer, most preferred first. Used to break ties between encodings the
+    /// client accepts equally.
+    pub encodings: Vec<ContentEncoding>,
+    /// gzip level, from 0 to 9.
+    pub gzip_level: u32,
+    /// brotli quality, from 0 to 11.
+    pub brotli_quality: u32,
+    /// zstd level, from 1 to 22.
+    pub zstd_level: i32,
+    /// Buffered responses smaller than this many bytes are sent uncompressed. Streamed responses
+    /// are always compressed, since their size isn't known up front.
+    pub min_size: usize,
+}
+
+impl Default for CompressionOptions {
+    fn default() -> Self {
+        // Lower levels than the maximum, since we compress every response as it's sent
+        Self {
+            encodings: vec![
+                ContentEncoding::Brotli,
+                ContentEncoding::Zstd,
+                ContentEncoding::Gzip,
+            ],
+            gzip_level: 6,
+            brotli_quality: 4,
+            zstd_level: 3,
+            min_size: 1024,
+        }
+    }
+}
+
+/// Pick the encoding to use for a request from its `Accept-Encoding` header, or `None` if
+/// compression is off or the client doesn't accept any encoding we offer. Compression is turned on
+/// per app, with [`PavexRouteIndex::with_compression`](crate::PavexRouteIndex::with_compression).
+pub fn negotiate(
+    options: Option<&CompressionOptions>,
+    headers: &HeaderMap,
+) -> Option<ContentEncoding> {
+    let options = options?;
+
+    let accepted = headers
+        .get_all(ACCEPT_ENCODING)
+        .iter()
+        .filter_map(|h| h.to_str().ok())
+        .flat_map(|h| h.split(','))
+        .filter_map(|item| {
+            let mut parts = item.split(';');
+            let name = parts.next()?.trim().to_ascii_lowercase();
+            let quality = parts
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .map(|q| q.trim().parse::<f32>().unwrap_or(0.0))
+                .unwrap_or(1.0);
+            Some((name, quality))
+        })
+        .collect::<Vec<_>>();
+
+    let quality_of = |encoding: &ContentEncoding| {
+        accepted
+            .iter()
+            .find(|(name, _)| name == encoding.as_str())
+            .or_else(|| accepted.iter().find(|(name, _)| name == "*"))
+            .map(|(_, quality)| *quality)
+            .unwrap_or(0.0)
+    };
+
+    let mut best: Option<(ContentEncoding, f32)> = None;
+    for encoding in options.encodings.iter() {
+        let quality = quality_of(encoding);
+        if quality > 0.0 && best.map_or(true, |(_, best_quality)| quality > best_quality) {
+            best = Some((*encoding, quality));
+        }
+    }
+    best.map(|(encoding, _)| encoding)
+}
+
+/// Compress the body of a response with the given encoding. Every frame of the body is flushed
+/// through the encoder as soon as it arrives, so streamed pages still reach the client chunk by
+/// chunk.
+pub fn compress_response(
+    options: Option<&CompressionOptions>,
+    encoding: Option<ContentEncoding>,
+    mut res: Response,
+) -> Response {
+    let Some(options) = options else {
+        return res;
+    };
+    if res.headers().contains_key(CONTENT_ENCODING) {
+        return res;
+    }
+    // Another request for the same page may get a different encoding, even if this one isn't
+    // compressed, so shared caches have to keep them apart
+    vary_on_accept_encoding(res.headers_mut());
+    let Some(encoding) = encoding else {
+        return res;
+    };
+
+    let status = res.status();
+    let mut headers = res.headers().clone();
+    let (_, body) = res.into_parts();
+
+    // Buffered bodies know their size. Don't bother with the small ones
+    if body
+        .size_hint()
+        .exact()
+        .is_some_and(|size| size < options.min_size as u64)
+    {
+        let mut res = Response::new(status).set_raw_body(body);
+        *res.headers_mut() = headers;
+        return res;
+    }
+
+    let encoder = match Encoder::new(encoding, options) {
+        Ok(encoder) => encoder,
+        Err(e) => {
+            tracing::warn!("Failed to create the {} encoder: {e}", encoding.as_str());
+            let mut res = Response::new(status).set_raw_body(body);
+            *res.headers_mut() = headers;
+            return res;
+        }
+    };
+
+    headers.remove(CONTENT_LENGTH);
+    headers.insert(
+        CONTENT_ENCODING,
+        HeaderValue::from_static(encoding.as_str()),
+    );
+
+    let mut res = Response::new(status).set_raw_body(CompressedBody {
+        inner: body,
+        encoder: Some(encoder),
+    });
+    *res.headers_mut() = headers;
+    res
+}
+
+/// Add `Vary: Accept-Encoding`, unless the response already varies on it, or on everything.
+fn vary_on_accept_encoding(headers: &mut HeaderMap) {
+    let varies = headers
+        .get_all(VARY)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .any(|name| name == "*" || name.eq_ignore_ascii_case("accept-encoding"));
+    if !varies {
+        headers.append(VARY, HeaderValue::from_static("accept-encoding"));
+    }
+}
+
+enum Encoder {
+    Gzip(GzEncoder<Vec<u8>>),
+    Brotli(Box<brotli::CompressorWriter<Vec<u8>>>),
+    Zstd(zstd::stream::write::Encoder<'static, Vec<u8>>),
+}
+
+impl Encoder {
+    fn new(encoding: ContentEncoding, options: &CompressionOptions) -> io::Result<Self> {
+        Ok(match encoding {
+            ContentEncoding::Gzip => Encoder::Gzip(GzEncoder::new(
+                Vec::new(),
+                Compression::new(options.gzip_level),
+            )),
+            ContentEncoding::Brotli => Encoder::Brotli(Box::new(brotli::CompressorWriter::new(
+                Vec::new(),
+                4096,
+                options.brotli_quality,
+                22,
+            ))),
+            ContentEncoding::Zstd => Encoder::Zstd(zstd::stream::write::Encoder::new(
+                Vec::new(),
+                options.zstd_level,
+            )?),
+        })
+    }
+
+    /// Compress a chunk and flush it, returning everything the encoder has produced so far.
+    fn compress(&mut self, data: &[u8]) -> io::Result<Bytes> {
+        let buf = match self {
+            Encoder::Gzip(encoder) => {
+                encoder.write_all(data)?;
+                encoder.flush()?;
+                encoder.get_mut()
+            }
+            Encoder::Brotli(encoder) => {
+                encoder.write_all(data)?;
+                encoder.flush()?;
+                encoder.get_mut()
+            }
+            Encoder::Zstd(encoder) => {
+                encoder.write_all(data)?;
+                encoder.flush()?;
+                encoder.get_mut()
+            }
+        };
+        Ok(std::mem::take(buf).into())
+    }
+
+    /// End the compressed stream, returning the remaining output.
+    fn finish(self) -> io::Result<Bytes> {
+        let buf = match self {
+            Encoder::Gzip(encoder) => encoder.finish()?,
+            Encoder::Brotli(encoder) => encoder.into_inner(),
+            Encoder::Zstd(encoder) => encoder.finish()?,
+        };
+        Ok(buf.into())
+    }
+}
+
+#[pin_project]
+struct CompressedBody<B> {
+    #[pin]
+    inner: B,
+    encoder: Option<Encoder>,
+}
+
+impl<B> RawBody for CompressedBody<B>
+where
+    B: RawBody<Data = Bytes>,
+    B::Error: Into<Box<dyn Error + Send + Sync>>,
+{
+    type Data = Bytes;
+
+    type Error = Box<dyn Error + Send + Sync>;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let mut this = self.project();
+        loop {
+            let Some(encoder) = this.encoder.as_mut() else {
+                return Poll::Ready(None);
+            };
+            match this.inner.as_mut().poll_frame(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e.into()))),
+                Poll::Ready(Some(Ok(frame))) => {
+                    let Ok(data) = frame.into_data() else {
+                        // We don't send trailers, and they'd need to come after the whole
+                        // compressed body anyway
+                        continue;
+                    };
+                    let compressed = encoder.compress(&data)?;
+                    // Encoders may hold on to small inputs until they have enough to emit
+                    if !compressed.is_empty() {
+                        return Poll::Ready(Some(Ok(Frame::data(compressed))));
+                    }
+                }
+                Poll::Ready(None) => {
+                    let encoder = this.encoder.take().expect("checked above");
+                    return Poll::Ready(Some(Ok(Frame::data(encoder.finish()?))));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accepting(accept_encoding: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT_ENCODING, HeaderValue::from_static(accept_encoding));
+        headers
+    }
+
+    fn negotiate_with_defaults(accept_encoding: &'static str) -> Option<ContentEncoding> {
+        negotiate(
+            Some(&CompressionOptions::default()),
+            &accepting(accept_encoding),
+        )
+    }
+
+    #[test]
+    fn nothing_is_compressed_when_compression_is_off() {
+        assert_eq!(negotiate(None, &accepting("gzip, br")), None);
+    }
+
+    #[test]
+    fn our_order_breaks_ties() {
+        assert_eq!(
+            negotiate_with_defaults("gzip, zstd, br"),
+            Some(ContentEncoding::Brotli)
+        );
+        assert_eq!(
+            negotiate_with_defaults("gzip, zstd"),
+            Some(ContentEncoding::Zstd)
+        );
+    }
+
+    #[test]
+    fn higher_q_values_win() {
+        assert_eq!(
+            negotiate_with_defaults("br;q=0.5, gzip;q=0.9"),
+            Some(ContentEncoding::Gzip)
+        );
+        assert_eq!(
+            negotiate_with_defaults("br; q=0.2, zstd ;q=0.8, gzip"),
+            Some(ContentEncoding::Gzip)
+        );
+    }
+
+    #[test]
+    fn encodings_with_a_q_of_zero_are_refused() {
+        assert_eq!(
+            negotiate_with_defaults("br;q=0, zstd;q=0, gzip"),
+            Some(ContentEncoding::Gzip)
+        );
+        assert_eq!(negotiate_with_defaults("gzip;q=0"), None);
+        assert_eq!(negotiate_with_defaults("gzip;q=nope"), None);
+    }
+
+    #[test]
+    fn the_wildcard_covers_encodings_not_listed() {
+        assert_eq!(negotiate_with_defaults("*"), Some(ContentEncoding::Brotli));
+        assert_eq!(
+            negotiate_with_defaults("br;q=0, *;q=0.5"),
+            Some(ContentEncoding::Zstd)
+        );
+        assert_eq!(negotiate_with_defaults("*;q=0"), None);
+    }
+
+    #[test]
+    fn identity_only_clients_get_no_encoding() {
+        assert_eq!(negotiate_with_defaults("identity"), None);
+        assert_eq!(
+            negotiate(Some(&CompressionOptions::default()), &HeaderMap::new()),
+            None
+        );
+    }
+}
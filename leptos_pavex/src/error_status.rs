@@ -0,0 +1,94 @@
+use leptos::server_fn::error::{NoCustomError, ServerFnError};
+use pavex::http::StatusCode;
+
+/// The status to answer a server function with when it fails with this custom error, e.g. a
+/// `404 Not Found` for a missing record. Every custom error a server function returns has to
+/// implement it. An empty impl keeps the default, `500 Internal Server Error`. A server function
+/// can also set the status on its [`ResponseOptions`](crate::response_options::ResponseOptions)
+/// before returning the error, which wins over both.
+pub trait ErrorStatus {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::INTERNAL_SERVER_ERROR
+    }
+}
+
+impl ErrorStatus for NoCustomError {}
+
+/// The status to answer a failed server function with. Errors the client caused, like arguments
+/// that can't be deserialized, are `400 Bad Request`, custom errors get the one from their
+/// [`ErrorStatus`], and the rest are `500 Internal Server Error`.
+pub fn status_code_for<E>(err: &ServerFnError<E>) -> StatusCode
+where
+    E: ErrorStatus,
+{
+    match err {
+        ServerFnError::WrappedServerError(e) => e.status_code(),
+        ServerFnError::Request(_)
+        | ServerFnError::Deserialization(_)
+        | ServerFnError::Args(_)
+        | ServerFnError::MissingArg(_) => StatusCode::BAD_REQUEST,
+        ServerFnError::Registration(_)
+        | ServerFnError::Response(_)
+        | ServerFnError::ServerError(_)
+        | ServerFnError::Serialization(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::response::PavexResponse;
+    use leptos::server_fn::response::Res;
+    use pavex::http::header::LOCATION;
+
+    #[derive(Debug)]
+    enum ShopError {
+        NotFound,
+        Closed,
+    }
+
+    impl ErrorStatus for ShopError {
+        fn status_code(&self) -> StatusCode {
+            match self {
+                ShopError::NotFound => StatusCode::NOT_FOUND,
+                ShopError::Closed => StatusCode::SERVICE_UNAVAILABLE,
+            }
+        }
+    }
+
+    #[derive(Debug)]
+    struct DefaultStatusError;
+
+    impl ErrorStatus for DefaultStatusError {}
+
+    #[test]
+    fn errors_the_client_caused_are_bad_requests() {
+        let err = ServerFnError::<NoCustomError>::Args("missing `id`".to_string());
+        assert_eq!(status_code_for(&err), StatusCode::BAD_REQUEST);
+        let err = ServerFnError::<NoCustomError>::ServerError("database is down".to_string());
+        assert_eq!(status_code_for(&err), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[test]
+    fn custom_errors_pick_their_own_status() {
+        let err = ServerFnError::WrappedServerError(ShopError::NotFound);
+        assert_eq!(status_code_for(&err), StatusCode::NOT_FOUND);
+        let err = ServerFnError::WrappedServerError(ShopError::Closed);
+        assert_eq!(status_code_for(&err), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[test]
+    fn custom_errors_without_a_status_are_internal_errors() {
+        let err = ServerFnError::WrappedServerError(DefaultStatusError);
+        assert_eq!(status_code_for(&err), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[test]
+    fn failed_form_posts_keep_their_status() {
+        let err = ServerFnError::<NoCustomError>::MissingArg("quantity".to_string());
+        let mut res = <PavexResponse as Res<NoCustomError>>::error_response("/api/buy", &err);
+        <PavexResponse as Res<NoCustomError>>::redirect(&mut res, "/shop");
+        assert_eq!(res.0.status(), StatusCode::BAD_REQUEST);
+        assert!(res.0.headers().get(LOCATION).is_none());
+    }
+}
@@ -1,7 +1,14 @@
+use crate::pavex_helpers::{AdditionalContextComponent, AppFunction};
+use crate::{render_app_to_stream, static_routes, PavexRouteIndex};
+use leptos::config::LeptosOptions;
 use pavex::http::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use pavex::http::{Method, StatusCode};
+use pavex::request::body::RawIncomingBody;
 use pavex::request::path::PathParams;
+use pavex::request::RequestHead;
 use pavex::response::body::raw::Full;
 use pavex::response::Response;
+use reactive_graph::computed::ScopedFuture;
 use std::fs;
 use std::path::Path;
 
@@ -14,23 +21,74 @@ pub fn serve_files(subpath: &PathParams<SubPath>) -> Response {
     let prefix = "target/site";
 
     // TODO: Here's where we would modify it for the incoming path. Check how Leptos does it
-    let basepath = Path::new(&format!("./{}", prefix)).to_path_buf();
-    let mut path = match basepath.join(subpath.0.path).canonicalize() {
+    serve_file(prefix, subpath.0.path).unwrap_or_else(Response::not_found)
+}
+
+/// A fallback handler for paths that no route matches. Serves the file at that path under the
+/// site root if there is one, with the route index's base path taken off, and otherwise renders
+/// the app with a `404 Not Found` status, so the `fallback` view of your `<Routes/>` reaches the
+/// user inside your layout. Register it with
+/// `bp.fallback(f!(leptos_pavex::file_helpers::file_and_error_handler))`.
+pub async fn file_and_error_handler(
+    routes: &PavexRouteIndex,
+    options: &LeptosOptions,
+    req_head: RequestHead,
+    req_body: RawIncomingBody,
+    context: AdditionalContextComponent,
+    app_fn: AppFunction,
+) -> Response {
+    // Paths outside of the base path aren't ours to render
+    let Some(path) = routes.base_path().strip(req_head.target.path()) else {
+        return Response::not_found();
+    };
+    if matches!(req_head.method, Method::GET | Method::HEAD) {
+        if let Some(res) = serve_file(&options.site_root, path.trim_start_matches('/')) {
+            return res;
+        }
+    }
+
+    #[cfg(feature = "compression")]
+    let encoding = crate::compression::negotiate(routes.compression(), &req_head.headers);
+    let mut res = context
+        .owner()
+        .with(|| ScopedFuture::new(render_app_to_stream(req_head, req_body, app_fn)))
+        .await;
+    // Keep any other status the app set, like a redirect
+    if res.status() == StatusCode::OK {
+        *res.status_mut() = StatusCode::NOT_FOUND;
+    }
+    #[cfg(feature = "compression")]
+    let res = crate::compression::compress_response(routes.compression(), encoding, res);
+    res
+}
+
+/// Serve the file at `path` under `root`, or `None` if there's no such file. Paths that resolve
+/// to somewhere outside of `root`, and the headers stored next to static pages, are treated as
+/// missing.
+fn serve_file(root: &str, path: &str) -> Option<Response> {
+    let basepath = match Path::new(root).canonicalize() {
         Ok(p) => p,
         Err(e) => {
-            eprintln!("Path Failure: {e}");
-            return Response::not_found();
+            tracing::error!("Failed to find the site root {root}: {e}");
+            return None;
         }
     };
+    let mut path = basepath.join(path).canonicalize().ok()?;
+    if !path.starts_with(&basepath) {
+        return None;
+    }
 
     if path.is_dir() {
         path.push("index.html");
     }
+    if static_routes::is_page_metadata(&path) {
+        return None;
+    }
 
     match path.try_exists() {
         Ok(true) => {}
-        Ok(false) => return Response::not_found(),
-        Err(_) => return Response::internal_server_error(),
+        Ok(false) => return None,
+        Err(_) => return Some(Response::internal_server_error()),
     }
 
     let mime = mime_guess::from_path(&path)
@@ -39,13 +97,13 @@ pub fn serve_files(subpath: &PathParams<SubPath>) -> Response {
 
     let hv = pavex::http::HeaderValue::from_str(&mime).expect("valid mime type");
 
-    match fs::read(path) {
+    Some(match fs::read(path) {
         Ok(file) => Response::ok()
             .append_header(CONTENT_TYPE, hv)
             .append_header(CONTENT_LENGTH, file.len().into())
             .set_raw_body(Full::new(file.into())),
         Err(_) => Response::internal_server_error(),
-    }
+    })
 }
 
 pub fn index() -> Response {
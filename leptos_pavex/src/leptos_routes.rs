@@ -1,56 +1,482 @@
 use http::Method;
+use leptos::server_fn::ServerFn;
 use leptos_router::Method as LeptosMethod;
 use pavex::{
     blueprint::{
-        router::{DELETE, GET, PATCH, POST, PUT},
+        router::{MethodGuard, DELETE, GET, PATCH, POST, PUT},
         Blueprint,
     },
     f,
+    http::{header::LOCATION, HeaderValue, StatusCode},
+    request::RequestHead,
+    response::Response,
 };
 
+use crate::base_path::BasePath;
+use crate::route_conflicts::{check_route_conflicts, RegisteredRoute, RouteOrigin};
+use crate::server_fn::ServerFnRegistry;
 use crate::{init_executor, PavexRouteList};
 
+/// How routes that differ from their Leptos definition only by a trailing slash are handled.
+/// The path as it's defined in the Leptos router is the canonical one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TrailingSlash {
+    /// Only the canonical path is registered, the other one is a 404.
+    #[default]
+    Strict,
+    /// The other path answers with a `308 Permanent Redirect` to the canonical path.
+    Redirect,
+    /// Both paths render the route.
+    Both,
+}
+
+/// The handlers the routes of one Leptos app are registered with. The defaults register pages to
+/// [`render_route`](crate::render_route) and every server function to
+/// [`handle_server_fns`](crate::server_fn::handle_server_fns).
+///
+/// To have more than one app in a Blueprint, give each app its own types for the route index,
+/// options, context and app function, so Pavex can tell them apart. Then write a handler for each
+/// app that unwraps them and calls `render_route`, and another that calls
+/// [`handle_server_fns_with_context`](crate::server_fn::handle_server_fns_with_context), and
+/// register the app's routes with those:
+///
+/// ```rust,ignore
+/// LeptosAppHandlers {
+///     register_page: |bp, method, path| {
+///         bp.route(method, path, f!(crate::admin::render_route));
+///     },
+///     register_server_fn: |bp, method, path| {
+///         bp.route(method, path, f!(crate::admin::handle_server_fns));
+///     },
+///     server_fns: ADMIN_SERVER_FNS,
+/// }
+/// ```
+///
+/// Server functions are all registered in the same place, so each app picks the ones that belong
+/// to it with a [`ServerFnRegistry`], usually by giving them a common prefix. Attach the same
+/// registry to the app's route index, see `examples/start_pavex/app/src/admin.rs`.
+#[derive(Clone, Copy, Debug)]
+pub struct LeptosAppHandlers {
+    /// Registers a page route of the app.
+    pub register_page: fn(&mut Blueprint, MethodGuard, &str),
+    /// Registers a server function of the app.
+    pub register_server_fn: fn(&mut Blueprint, MethodGuard, &str),
+    /// The server functions that belong to the app.
+    pub server_fns: ServerFnRegistry,
+}
+
+impl Default for LeptosAppHandlers {
+    fn default() -> Self {
+        Self {
+            register_page: |bp, method, path| {
+                bp.route(method, path, f!(crate::render_route));
+            },
+            register_server_fn: |bp, method, path| {
+                bp.route(method, path, f!(crate::server_fn::handle_server_fns));
+            },
+            server_fns: ServerFnRegistry::default(),
+        }
+    }
+}
+
+/// Pavex middleware for a group of server functions: a single one, or every server function under
+/// a path prefix. `register` gets a nested Blueprint that holds only the server functions of the
+/// group, so the middleware you register on it with `bp.wrap`, `bp.pre_process` or
+/// `bp.post_process` wraps just those routes, and can take any component of your Blueprint, like
+/// a database pool or the authenticated user:
+///
+/// ```rust,ignore
+/// ServerFnMiddleware::for_prefix("/api/admin", |bp| {
+///     bp.wrap(f!(crate::auth::require_admin));
+/// })
+/// ```
+///
+/// The one thing a wrapping middleware can't take is a `&RequestHead`: `handle_server_fns` takes
+/// the request head by value, so it can't stay borrowed while `next` runs. Take what you need
+/// from it in a constructor, or take the `MatchedPathPattern`, which is the server function's path.
+///
+/// A server function in more than one group is wrapped by all of them, in the order they're
+/// listed in [`LeptosRoutesOptions::server_fn_middleware`], the first one outermost. Middleware on
+/// the outer Blueprint still runs around all of it.
+///
+/// Pavex middleware wraps the whole request, so it runs before the request is handed to Leptos
+/// and before the server function is picked by its path. The `middlewares()` of the server
+/// function, the ones from `#[middleware]`, run inside of it, right around the function itself.
+/// So the order on the way in is: Pavex middleware, then `handle_server_fns` (which provides
+/// the Leptos context and checks the body limit), then the Leptos middlewares, then the server
+/// function. A Pavex middleware that answers without calling `next` keeps the server function
+/// from running at all, and one that looks at the response sees it after the Leptos
+/// middlewares and any redirect have been applied.
+#[derive(Clone, Debug)]
+pub struct ServerFnMiddleware {
+    path: String,
+    exact: bool,
+    register: fn(&mut Blueprint),
+}
+
+impl ServerFnMiddleware {
+    /// Middleware for every server function whose path is `prefix` or under it. The prefix is
+    /// matched by whole segments against the path the server function was compiled with, without
+    /// the base path.
+    pub fn for_prefix(prefix: impl Into<String>, register: fn(&mut Blueprint)) -> Self {
+        Self {
+            path: prefix.into().trim_end_matches('/').to_string(),
+            exact: false,
+            register,
+        }
+    }
+
+    /// Middleware for the server function `T` only.
+    pub fn for_server_fn<T>(register: fn(&mut Blueprint)) -> Self
+    where
+        T: ServerFn,
+    {
+        Self {
+            path: T::PATH.to_string(),
+            exact: true,
+            register,
+        }
+    }
+
+    fn matches(&self, path: &str) -> bool {
+        if self.exact {
+            return path == self.path;
+        }
+        path == self.path
+            || path
+                .strip_prefix(self.path.as_str())
+                .is_some_and(|rest| rest.starts_with('/'))
+    }
+}
+
+/// Options for [`generate_leptos_routes_with_options`].
+#[derive(Clone, Debug, Default)]
+pub struct LeptosRoutesOptions {
+    /// How paths with or without a trailing slash are handled.
+    pub trailing_slash: TrailingSlash,
+    /// The handlers this app's routes are registered with.
+    pub handlers: LeptosAppHandlers,
+    /// The routes you register on the Blueprint by hand, as method and Pavex path. Pavex doesn't
+    /// let us look at the routes already on a Blueprint, so list them here to have them checked
+    /// for conflicts with the Leptos routes and server functions. With more than one app, list the
+    /// routes of the others here too.
+    pub pavex_routes: Vec<(Method, String)>,
+    /// Pavex middleware for some of the server functions, see [`ServerFnMiddleware`].
+    pub server_fn_middleware: Vec<ServerFnMiddleware>,
+    /// The path prefix the app is mounted under. Server functions are registered under it. The
+    /// page routes already are, since the app declares them under it, and this panics if one of
+    /// them isn't. See [`BasePath`] for everything else that needs it.
+    pub base_path: BasePath,
+}
+
+/// Registers the Leptos routes and every server function on the Blueprint. Server functions are
+/// registered at the path they were compiled with, so the prefix configured for
+/// `leptos_pavex_macro::server` applies here too.
 pub fn generate_leptos_routes(paths: &PavexRouteList, bp: &mut Blueprint) {
+    generate_leptos_routes_with_options(paths, bp, LeptosRoutesOptions::default())
+}
+
+/// Like [`generate_leptos_routes`], but lets you choose how paths with or without a trailing slash
+/// are handled.
+pub fn generate_leptos_routes_with_trailing_slash(
+    paths: &PavexRouteList,
+    bp: &mut Blueprint,
+    trailing_slash: TrailingSlash,
+) {
+    generate_leptos_routes_with_options(
+        paths,
+        bp,
+        LeptosRoutesOptions {
+            trailing_slash,
+            ..Default::default()
+        },
+    )
+}
+
+/// Like [`generate_leptos_routes`], with [`LeptosRoutesOptions`]. The routes are checked for
+/// conflicts with each other and with the hand-written routes in the options before anything is
+/// registered, and this panics with every conflict it found, since Pavex's own errors for them
+/// come much later and are harder to trace back.
+pub fn generate_leptos_routes_with_options(
+    paths: &PavexRouteList,
+    bp: &mut Blueprint,
+    options: LeptosRoutesOptions,
+) {
     init_executor();
 
-    // register server functions
-    for (path, method) in crate::server_fn::server_fn_paths() {
-        let method = match method {
+    let mut routes = Vec::new();
+
+    // server functions
+    for (path, method) in options.handlers.server_fns.paths() {
+        let groups = middleware_groups(&options.server_fn_middleware, path);
+        routes.push((
+            RegisteredRoute::new(
+                method,
+                options.base_path.prefix(path),
+                RouteOrigin::ServerFn,
+            ),
+            Handler::ServerFn { groups },
+        ));
+    }
+
+    // router paths
+    // The app declares them under the base path, the `base` of the `<Router/>` isn't added
+    let outside_base_path = paths
+        .iter()
+        .map(|listing| listing.path())
+        .filter(|path| !options.base_path.contains(path))
+        .collect::<Vec<_>>();
+    if !outside_base_path.is_empty() {
+        panic!(
+            "The Leptos routes {} aren't under the base path {}, declare them under it, e.g. \
+             with a `StaticSegment` of it in front of their own path",
+            outside_base_path.join(", "),
+            options.base_path
+        );
+    }
+    for listing in paths.iter() {
+        let path = listing.path();
+        let other_path = toggle_trailing_slash(path);
+        for method in listing.methods() {
+            let method = match method {
+                LeptosMethod::Get => Method::GET,
+                LeptosMethod::Post => Method::POST,
+                LeptosMethod::Put => Method::PUT,
+                LeptosMethod::Delete => Method::DELETE,
+                LeptosMethod::Patch => Method::PATCH,
+            };
+            routes.push((
+                RegisteredRoute::new(method.clone(), path, RouteOrigin::Leptos),
+                Handler::Render,
+            ));
+            if let Some(other_path) = &other_path {
+                let handler = match options.trailing_slash {
+                    TrailingSlash::Strict => continue,
+                    TrailingSlash::Redirect => Handler::RedirectTrailingSlash,
+                    TrailingSlash::Both => Handler::Render,
+                };
+                routes.push((
+                    RegisteredRoute::new(method, other_path, RouteOrigin::Leptos),
+                    handler,
+                ));
+            }
+        }
+    }
+
+    let mut all_routes = routes
+        .iter()
+        .map(|(route, _)| route.clone())
+        .collect::<Vec<_>>();
+    all_routes.extend(
+        options
+            .pavex_routes
+            .iter()
+            .map(|(method, path)| RegisteredRoute::new(method.clone(), path, RouteOrigin::Pavex)),
+    );
+    if let Err(conflicts) = check_route_conflicts(&all_routes) {
+        panic!("{conflicts}");
+    }
+
+    // Server functions with middleware go on a nested Blueprint for each set of groups they're in
+    let mut nested = Vec::new();
+    for (route, handler) in routes {
+        let method = match route.method {
             Method::GET => GET,
             Method::POST => POST,
             Method::PUT => PUT,
             Method::DELETE => DELETE,
             Method::PATCH => PATCH,
-            _ => {
-                panic!(
-                    "Unsupported server function HTTP method: \
-                 {method:?}"
+            method => {
+                panic!("Unsupported {} HTTP method: {method:?}", route.origin);
+            }
+        };
+        let path = route.path.as_str();
+        match handler {
+            Handler::ServerFn { groups } if groups.is_empty() => {
+                (options.handlers.register_server_fn)(bp, method, path)
+            }
+            Handler::ServerFn { groups } => nested.push((groups, (method, route.path.clone()))),
+            Handler::Render => (options.handlers.register_page)(bp, method, path),
+            Handler::RedirectTrailingSlash => {
+                bp.route(
+                    method,
+                    path,
+                    f!(crate::leptos_routes::redirect_trailing_slash),
                 );
             }
+        }
+    }
+
+    for (groups, routes) in nest_by_groups(nested) {
+        let mut nested_bp = Blueprint::new();
+        // Pavex middleware only wraps the routes registered after it
+        for i in groups {
+            (options.server_fn_middleware[i].register)(&mut nested_bp);
+        }
+        for (method, path) in routes {
+            (options.handlers.register_server_fn)(&mut nested_bp, method, &path);
+        }
+        bp.nest(nested_bp);
+    }
+}
+
+/// The indices of the [`ServerFnMiddleware`] groups the server function at `path` is in.
+fn middleware_groups(middleware: &[ServerFnMiddleware], path: &str) -> Vec<usize> {
+    middleware
+        .iter()
+        .enumerate()
+        .filter(|(_, group)| group.matches(path))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Gather the server functions that are in the same set of middleware groups, in the order each
+/// set first shows up, so every set gets one nested Blueprint.
+fn nest_by_groups<T>(routes: Vec<(Vec<usize>, T)>) -> Vec<(Vec<usize>, Vec<T>)> {
+    let mut nested: Vec<(Vec<usize>, Vec<T>)> = Vec::new();
+    for (groups, route) in routes {
+        match nested.iter_mut().find(|(g, _)| *g == groups) {
+            Some((_, routes)) => routes.push(route),
+            None => nested.push((groups, vec![route])),
+        }
+    }
+    nested
+}
+
+/// The request handler a generated route is registered with.
+enum Handler {
+    /// The indices of the [`ServerFnMiddleware`] groups the server function is in.
+    ServerFn {
+        groups: Vec<usize>,
+    },
+    Render,
+    RedirectTrailingSlash,
+}
+
+/// Redirects a request to the same path with the trailing slash added or removed. This is
+/// registered for the non-canonical form of each route with [`TrailingSlash::Redirect`].
+pub fn redirect_trailing_slash(req_head: &RequestHead) -> Response {
+    let path = req_head.target.path();
+    let Some(mut location) = toggle_trailing_slash(path) else {
+        return Response::not_found();
+    };
+    if let Some(query) = req_head.target.query() {
+        location.push('?');
+        location.push_str(query);
+    }
+    match HeaderValue::from_str(&location) {
+        Ok(location) => {
+            Response::new(StatusCode::PERMANENT_REDIRECT).insert_header(LOCATION, location)
+        }
+        Err(_) => Response::not_found(),
+    }
+}
+
+/// The same path with a trailing slash added or removed. Returns `None` for paths that don't have
+/// another form, like the root or a path that ends in a wildcard.
+pub(crate) fn toggle_trailing_slash(path: &str) -> Option<String> {
+    if path == "/" || path.is_empty() {
+        return None;
+    }
+    if let Some(stripped) = path.strip_suffix('/') {
+        return Some(stripped.to_string());
+    }
+    let last_segment = path.rsplit('/').next().unwrap_or_default();
+    if last_segment.starts_with('*') {
+        return None;
+    }
+    Some(format!("{path}/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::static_routes::static_request_head;
+
+    fn middleware(prefixes: &[&str]) -> Vec<ServerFnMiddleware> {
+        prefixes
+            .iter()
+            .map(|prefix| ServerFnMiddleware::for_prefix(*prefix, |_| {}))
+            .collect()
+    }
+
+    #[test]
+    fn prefixes_match_whole_segments() {
+        let group = ServerFnMiddleware::for_prefix("/api/admin/", |_| {});
+        assert!(group.matches("/api/admin"));
+        assert!(group.matches("/api/admin/stats"));
+        assert!(!group.matches("/api/administrator"));
+        assert!(!group.matches("/api"));
+    }
+
+    #[test]
+    fn single_server_fn_middleware_only_matches_its_path() {
+        let group = ServerFnMiddleware {
+            path: "/api/redirect_to".to_string(),
+            exact: true,
+            register: |_| {},
         };
-        bp.route(
-            
-            method,
-            path,
-            f!(crate::server_fn::handle_server_fns),
+        assert!(group.matches("/api/redirect_to"));
+        assert!(!group.matches("/api/redirect_to/more"));
+    }
+
+    #[test]
+    fn server_fns_are_in_every_group_that_matches() {
+        let middleware = middleware(&["/api", "/api/admin", "/other"]);
+        assert_eq!(middleware_groups(&middleware, "/api/admin/stats"), [0, 1]);
+        assert_eq!(middleware_groups(&middleware, "/api/greet"), [0]);
+        assert!(middleware_groups(&middleware, "/rpc/greet").is_empty());
+    }
+
+    #[test]
+    fn server_fns_in_the_same_groups_share_a_nested_blueprint() {
+        let nested = nest_by_groups(vec![
+            (vec![0], "/api/greet"),
+            (vec![0, 1], "/api/admin/stats"),
+            (vec![0], "/api/ping"),
+            (vec![1], "/other/admin"),
+            (vec![0, 1], "/api/admin/users"),
+        ]);
+        assert_eq!(
+            nested,
+            [
+                (vec![0], vec!["/api/greet", "/api/ping"]),
+                (vec![0, 1], vec!["/api/admin/stats", "/api/admin/users"]),
+                (vec![1], vec!["/other/admin"]),
+            ]
         );
     }
 
-    // register router paths
-    for listing in paths.iter() {
-        let path = listing.path();
-        for method in listing.methods() {
-            bp.route(
-                match method {
-                    LeptosMethod::Get => GET,
-                    LeptosMethod::Post => POST,
-                    LeptosMethod::Put => PUT,
-                    LeptosMethod::Delete => DELETE,
-                    LeptosMethod::Patch => PATCH,
-                },
-                path,
-                f!(crate::render_route),
-            );
-        }
+    #[test]
+    fn trailing_slashes_are_added_and_removed() {
+        assert_eq!(toggle_trailing_slash("/about"), Some("/about/".to_string()));
+        assert_eq!(toggle_trailing_slash("/about/"), Some("/about".to_string()));
+        assert_eq!(
+            toggle_trailing_slash("/post/:id"),
+            Some("/post/:id/".to_string())
+        );
+    }
+
+    #[test]
+    fn the_root_and_wildcards_have_no_other_form() {
+        assert_eq!(toggle_trailing_slash("/"), None);
+        assert_eq!(toggle_trailing_slash(""), None);
+        assert_eq!(toggle_trailing_slash("/docs/*rest"), None);
+    }
+
+    #[test]
+    fn the_other_form_is_redirected_to_with_its_query() {
+        let res = redirect_trailing_slash(&static_request_head("/about/?tab=team").unwrap());
+        assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+        assert_eq!(res.headers()[LOCATION], "/about?tab=team");
+
+        let res = redirect_trailing_slash(&static_request_head("/about").unwrap());
+        assert_eq!(res.headers()[LOCATION], "/about/");
+    }
+
+    #[test]
+    fn paths_without_another_form_are_not_found() {
+        let res = redirect_trailing_slash(&static_request_head("/").unwrap());
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
     }
 }
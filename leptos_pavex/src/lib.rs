@@ -1,16 +1,28 @@
+pub mod base_path;
+#[cfg(feature = "compression")]
+pub mod compression;
+pub mod error_status;
 #[allow(dead_code)]
-
 pub mod extend_response;
 #[cfg(feature = "ssr")]
 pub mod file_helpers;
 
 pub mod leptos_routes;
+pub mod page_cache;
+pub mod panic_page;
+pub mod path_params;
 pub mod pavex_helpers;
+mod preload;
+pub mod render_deadline;
 pub mod request;
 pub mod request_parts;
 pub mod response;
 pub mod response_options;
+pub mod route_artifact;
+pub mod route_conflicts;
+pub mod route_exclusion;
 pub mod server_fn;
+pub mod static_routes;
 pub mod stream;
 
 use bytes::Bytes;
@@ -19,33 +31,50 @@ use futures::stream::once;
 use futures::{Stream, StreamExt};
 use hydration_context::SsrSharedContext;
 use leptos::server_fn::redirect::REDIRECT_HEADER;
+use leptos::server_fn::ServerFn;
+use std::collections::HashMap;
 use std::io;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Instant;
 
 use crate::request_parts::RequestParts;
 use crate::response_options::ResponseOptions;
+use base_path::BasePath;
+use leptos::config::LeptosOptions;
 use leptos::prelude::{provide_context, use_context, Owner};
 use leptos::tachys::view::RenderHtml;
+use leptos::IntoView;
 use leptos_integration_utils::{BoxedFnOnce, PinnedFuture, PinnedStream};
 use leptos_meta::ServerMetaContext;
 use leptos_router::components::provide_server_redirect;
 use leptos_router::location::RequestUrl;
 use leptos_router::{PathSegment, RouteList, RouteListing, SsrMode, StaticDataMap, StaticMode};
+use page_cache::PageCache;
+use panic_page::PanicView;
+use path_params::PavexPathParams;
 use pavex::http::header::{ACCEPT, LOCATION};
 use pavex::http::uri::PathAndQuery;
 use pavex::http::StatusCode;
 use pavex::http::{HeaderName, HeaderValue};
-use pavex::request::body::RawIncomingBody;
-use pavex::request::path::MatchedPathPattern;
+use pavex::request::body::{BodySizeLimit, RawIncomingBody};
+use pavex::request::path::{MatchedPathPattern, RawPathParams};
 use pavex::request::RequestHead;
 use pavex::response::Response;
-use pavex_helpers::{AdditionalContextComponent, AppFunction};
+use pavex_helpers::{AdditionalContextComponent, AppFunction, AppRenderer};
 use reactive_graph::computed::ScopedFuture;
+use render_deadline::{OnRenderTimeout, RenderDeadline, RenderExpiry};
 use response::PavexResponse;
+use route_exclusion::RouteExclusion;
+use serde::{Deserialize, Serialize};
+use server_fn::ServerFnRegistry;
+use static_routes::IncrementalPages;
+use stream::ChunkBuffering;
 
 /// Provides an easy way to redirect the user from within a server function. Mimicking the Remix `redirect()`,
-/// it sets a StatusCode of 302 and a LOCATION header with the provided value.
+/// it sets a LOCATION header with the provided value. Plain HTML form posts get a 303 See Other, so
+/// the browser follows it with a GET, while requests from the server fn client get the
+/// REDIRECT_HEADER instead, so they can still read the return value and navigate client-side.
 /// If looking to redirect from the client, `leptos_router::use_navigate()` should be used instead
 pub fn redirect(path: &str) {
     if let (Some(req), Some(res)) = (
@@ -66,8 +95,8 @@ pub fn redirect(path: &str) {
             .unwrap_or(false);
         if accepts_html {
             // if the request accepts text/html, it's a plain form request and needs
-            // to have the 302 code set
-            res.set_status(StatusCode::FOUND);
+            // to have the 303 code set, so the browser follows it with a GET
+            res.set_status(StatusCode::SEE_OTHER);
         } else {
             // otherwise, we sent it from the server fn client and actually don't want
             // to set a real redirect, as this will break the ability to return data
@@ -111,40 +140,132 @@ pub async fn render_app_to_stream(
     render_app_to_stream_and_replace_blocks(req_head, req_body, app_fn, false).await
 }
 
-
+// Pavex injects each of these separately
+#[allow(clippy::too_many_arguments)]
 #[tracing::instrument(level = "trace", fields(error), skip_all)]
 pub async fn render_route(
-    paths: PavexRouteList,
+    routes: &PavexRouteIndex,
+    options: &LeptosOptions,
+    req_head: &RequestHead,
+    req_body: RawIncomingBody,
+    matched_path: &MatchedPathPattern,
+    path_params: &RawPathParams<'_, '_>,
+    context: AdditionalContextComponent,
+    app_fn: AppFunction,
+) -> Response {
+    #[cfg(feature = "compression")]
+    let encoding = compression::negotiate(routes.compression(), &req_head.headers);
+    // Hand the params Pavex matched to the app, so it doesn't need the router to read them
+    let path_params = PavexPathParams::from_raw(path_params);
+    context.owner().with(|| {
+        provide_context(path_params);
+        provide_app_contexts(routes);
+    });
+    let res = render_listing(
+        routes,
+        options,
+        owned_request_head(req_head),
+        req_body,
+        matched_path,
+        context,
+        app_fn,
+    )
+    .await;
+    // Compress last, so prerendered pages get compressed too
+    #[cfg(feature = "compression")]
+    let res = compression::compress_response(routes.compression(), encoding, res);
+    res
+}
+
+/// Copy the request head, so the render can own it. Pavex only lends it to handlers that also take
+/// the path params, since those borrow from the request path.
+fn owned_request_head(req_head: &RequestHead) -> RequestHead {
+    let (mut parts, _) = http::Request::new(()).into_parts();
+    parts.method = req_head.method.clone();
+    parts.uri = req_head.target.clone();
+    parts.version = req_head.version;
+    parts.headers = req_head.headers.clone();
+    parts.into()
+}
+
+async fn render_listing(
+    routes: &PavexRouteIndex,
+    options: &LeptosOptions,
     req_head: RequestHead,
     req_body: RawIncomingBody,
     matched_path: &MatchedPathPattern,
     context: AdditionalContextComponent,
     app_fn: AppFunction,
 ) -> Response {
-    // 1. Process route to match the values in routeListing
-    let path = &matched_path.to_string();
-    // 2. Find RouteListing in paths. This should probably be optimized, we probably don't want to
-    // search for this every time
-    let listing: &PavexRouteListing = paths
-        .iter()
-        .find(|r| r.path() == matched_path.inner())
-        .unwrap_or_else(|| {
-            panic!(
-                "Failed to find the route {path} requested by the user. \
-                    This suggests that the routing rules in the Router that \
-                    call this handler needs to be edited!"
-            )
-        });
+    // 1. Find the RouteListing for the path template Pavex matched
+    let Some(listing) = routes.get(matched_path.inner()) else {
+        tracing::error!(
+            "Failed to find the route {matched_path} requested by the user. \
+             This suggests that the routing rules in the Router that \
+             call this handler needs to be edited!"
+        );
+        return Response::internal_server_error();
+    };
+    // 2. Serve the prerendered page if this is a static route that's already been built
+    if let Some(res) = static_routes::serve_static_page(options, listing, &req_head) {
+        return res;
+    }
+    if listing.static_mode() == Some(StaticMode::Incremental) {
+        return static_routes::render_incremental(
+            options, routes, listing, req_head, context, app_fn,
+        )
+        .await;
+    }
     // 3. Match listing mode against known, and choose function
-    let owner = context.owner();
-    match listing.mode() {
+    let deadline = match listing.mode() {
+        SsrMode::InOrder | SsrMode::Async => routes.render_deadline(listing.path()),
+        SsrMode::OutOfOrder | SsrMode::PartiallyBlocked => None,
+    };
+    let Some(deadline) = deadline else {
+        return render_mode(listing.mode(), req_head, req_body, context.owner(), app_fn).await;
+    };
+
+    let expiry = RenderExpiry(Instant::now() + deadline.timeout);
+    context.owner().with(|| provide_context(expiry));
+    if let OnRenderTimeout::Stream = deadline.on_timeout {
+        // Render out of order from the start, so the page can switch to streaming its fallbacks
+        // when the deadline passes without rendering anything again
+        let owner = context.owner();
+        return match listing.mode() {
+            SsrMode::InOrder => {
+                let render = render_app_until_deadline::<true>(req_head, app_fn);
+                owner.with(|| ScopedFuture::new(render)).await
+            }
+            _ => {
+                let render = render_app_until_deadline::<false>(req_head, app_fn);
+                owner.with(|| ScopedFuture::new(render)).await
+            }
+        };
+    }
+    let render = render_mode(listing.mode(), req_head, req_body, context.owner(), app_fn);
+    if let Some(res) = render_deadline::render_until(expiry, render).await {
+        return res;
+    }
+    tracing::warn!(
+        "Render deadline of {:?} passed for {}, responding with the timeout page",
+        deadline.timeout,
+        listing.path()
+    );
+    render_deadline::timeout_page(deadline)
+}
+
+/// Render the app under `owner` the way `mode` says.
+async fn render_mode(
+    mode: SsrMode,
+    req_head: RequestHead,
+    req_body: RawIncomingBody,
+    owner: &Owner,
+    app_fn: AppFunction,
+) -> Response {
+    match mode {
         SsrMode::OutOfOrder => {
             owner
-                .with(|| {
-                    ScopedFuture::new(render_app_to_stream(
-                        req_head, req_body, app_fn,
-                    ))
-                })
+                .with(|| ScopedFuture::new(render_app_to_stream(req_head, req_body, app_fn)))
                 .await
         }
         SsrMode::PartiallyBlocked => {
@@ -159,33 +280,50 @@ pub async fn render_route(
         SsrMode::InOrder => {
             owner
                 .with(|| {
-                    ScopedFuture::new(render_app_to_stream_in_order(
-                        req_head, req_body, app_fn,
-                    ))
+                    ScopedFuture::new(render_app_to_stream_in_order(req_head, req_body, app_fn))
                 })
                 .await
         }
         SsrMode::Async => {
             owner
-                .with(|| {
-                    ScopedFuture::new(render_app_async(
-                        req_head, req_body, app_fn,
-                    ))
-                })
+                .with(|| ScopedFuture::new(render_app_async(req_head, req_body, app_fn)))
                 .await
         }
     }
 }
 
+/// Renders the app as an out-of-order HTML stream.
+///
+/// `replace_blocks` lets you specify whether `<Suspense/>` fragments should be retrojected into the
+/// HTML that's initially served, rather than dynamically inserting them with JavaScript on the
+/// client. This means you will have better support if JavaScript is not enabled, in exchange for a
+/// slower response time.
+///
+/// The pinned Leptos revision has no blocking resources to tell apart from the others, so with
+/// `replace_blocks` every `<Suspense/>` waits on its resources before it's flushed, the same way
+/// in-order streaming does.
 #[tracing::instrument(level = "trace", fields(error), skip_all)]
 pub async fn render_app_to_stream_and_replace_blocks(
     req_head: RequestHead,
-    req_body: RawIncomingBody,
+    _req_body: RawIncomingBody,
     app_fn: AppFunction,
     replace_blocks: bool,
 ) -> Response {
-    _ = replace_blocks; // TODO
-    handle_response(req_head, req_body, app_fn, |app, chunks| {
+    if replace_blocks {
+        handle_response(req_head, app_fn, |app, chunks| {
+            Box::pin(async move {
+                Box::pin(app.inner().to_html_stream_in_order().chain(chunks()))
+                    as PinnedStream<String>
+            })
+        })
+        .await
+    } else {
+        render_app_out_of_order(req_head, app_fn).await
+    }
+}
+
+async fn render_app_out_of_order(req_head: RequestHead, app_fn: AppFunction) -> Response {
+    handle_response(req_head, app_fn, |app, chunks| {
         Box::pin(async move {
             Box::pin(app.inner().to_html_stream_out_of_order().chain(chunks()))
                 as PinnedStream<String>
@@ -194,15 +332,43 @@ pub async fn render_app_to_stream_and_replace_blocks(
     .await
 }
 
+/// Render the app out of order, holding it back until its deadline as
+/// [`OnRenderTimeout::Stream`] says. In-order routes send the start of the page as soon as it's
+/// resolved.
+async fn render_app_until_deadline<const IN_ORDER: bool>(
+    req_head: RequestHead,
+    app_fn: AppFunction,
+) -> Response {
+    handle_response(req_head, app_fn, |app, chunks| {
+        let expiry = use_context::<RenderExpiry>();
+        Box::pin(async move {
+            let stream =
+                Box::pin(app.inner().to_html_stream_out_of_order()) as PinnedStream<String>;
+            let stream = match expiry {
+                Some(expiry) => render_deadline::resolve_until(stream, expiry, IN_ORDER),
+                None => stream,
+            };
+            Box::pin(stream.chain(chunks())) as PinnedStream<String>
+        })
+    })
+    .await
+}
+
 #[tracing::instrument(level = "trace", fields(error), skip_all)]
 pub async fn render_app_to_stream_in_order(
     req_head: RequestHead,
-    req_body: RawIncomingBody,
+    _req_body: RawIncomingBody,
     app_fn: AppFunction,
 ) -> Response {
-    handle_response(req_head, req_body, app_fn, |app, chunks| {
+    handle_response(req_head, app_fn, |app, chunks| {
+        let expiry = use_context::<RenderExpiry>();
         Box::pin(async move {
-            Box::pin(app.inner().to_html_stream_in_order().chain(chunks())) as PinnedStream<String>
+            let stream = Box::pin(app.inner().to_html_stream_in_order()) as PinnedStream<String>;
+            let stream = match expiry {
+                Some(expiry) => render_deadline::cut_off_at(stream, expiry),
+                None => stream,
+            };
+            Box::pin(stream.chain(chunks())) as PinnedStream<String>
         })
     })
     .await
@@ -210,7 +376,6 @@ pub async fn render_app_to_stream_in_order(
 
 async fn handle_response(
     req_head: RequestHead,
-    _req_body: RawIncomingBody,
     app_fn: AppFunction,
     stream_builder: fn(
         AppFunction,
@@ -218,7 +383,7 @@ async fn handle_response(
     ) -> PinnedFuture<PinnedStream<String>>,
 ) -> Response {
     let res_options = ResponseOptions::default();
-    let meta_context = ServerMetaContext::new();
+    let meta_context = current_meta_context();
 
     let additional_context = {
         let meta_context = meta_context.clone();
@@ -246,6 +411,13 @@ async fn handle_response(
     res.0
 }
 
+/// The meta context to inject into the page. The app is built before it's rendered, under the
+/// owner `provide_initial_contexts` ran in, so its `<Title/>` and friends are registered in the
+/// meta context provided there.
+pub(crate) fn current_meta_context() -> ServerMetaContext {
+    use_context::<ServerMetaContext>().unwrap_or_default()
+}
+
 /// Provide Context one might want available to people in the additional context environment
 #[tracing::instrument(level = "trace", fields(error), skip_all)]
 pub fn provide_initial_contexts(req_head: &RequestHead, parts: RequestParts) {
@@ -254,13 +426,24 @@ pub fn provide_initial_contexts(req_head: &RequestHead, parts: RequestParts) {
         .path_and_query()
         .cloned()
         .unwrap_or(PathAndQuery::from_static("/"));
-    provide_context(RequestUrl::new(&path.to_string()));
+    // The full path, base path included, as the router sees it in the browser. The app's routes
+    // are declared under the base path, see `BasePath`
+    provide_context(RequestUrl::new(path.as_str()));
     provide_context(parts);
     provide_context(ServerMetaContext::new());
     provide_server_redirect(redirect);
     #[cfg(feature = "nonce")]
     leptos::nonce::provide_nonce();
 }
+/// Provide the app's own handles, its route index and its page cache if it has one, so pages and
+/// server functions can invalidate and purge what they've changed.
+pub(crate) fn provide_app_contexts(routes: &PavexRouteIndex) {
+    provide_context(routes.clone());
+    if let Some(cache) = routes.page_cache() {
+        provide_context(cache.clone());
+    }
+}
+
 // Makes sure the stuff that could be added to context earlier is set, and add the remaining stuff
 #[tracing::instrument(level = "trace", fields(error), skip_all)]
 fn provide_post_contexts(
@@ -286,10 +469,10 @@ fn provide_post_contexts(
 #[tracing::instrument(level = "trace", fields(error), skip_all)]
 pub async fn render_app_async(
     req_head: RequestHead,
-    req_body: RawIncomingBody,
+    _req_body: RawIncomingBody,
     app_fn: AppFunction,
 ) -> Response {
-    handle_response(req_head, req_body, app_fn, |app, chunks| {
+    handle_response(req_head, app_fn, |app, chunks| {
         Box::pin(async move {
             let app = app
                 .inner()
@@ -308,7 +491,7 @@ pub async fn render_app_async(
 /// as an argument, so it can walk your app tree. This version is tailored to generate Axum compatible paths.
 #[tracing::instrument(level = "trace", fields(error), skip_all)]
 pub fn generate_route_list(app_fn: AppFunction) -> PavexRouteList {
-    generate_route_list_with_exclusions_and_ssg(app_fn, None).0
+    generate_route_list_with_exclusions_and_ssg(app_fn, std::iter::empty::<RouteExclusion>()).0
 }
 
 /// Generates a list of all routes defined in Leptos's Router in your app. We can then use this to automatically
@@ -316,17 +499,18 @@ pub fn generate_route_list(app_fn: AppFunction) -> PavexRouteList {
 /// as an argument, so it can walk your app tree. This version is tailored to generate Axum compatible paths.
 #[tracing::instrument(level = "trace", fields(error), skip_all)]
 pub fn generate_route_list_with_ssg(app_fn: AppFunction) -> (PavexRouteList, StaticDataMap) {
-    generate_route_list_with_exclusions_and_ssg(app_fn, None)
+    generate_route_list_with_exclusions_and_ssg(app_fn, std::iter::empty::<RouteExclusion>())
 }
 
 /// Generates a list of all routes defined in Leptos's Router in your app. We can then use this to automatically
 /// create routes in Axum's Router without having to use wildcard matching or fallbacks. Takes in your root app Element
 /// as an argument, so it can walk you app tree. This version is tailored to generate Axum compatible paths. Adding excluded_routes
-/// to this function will stop `.leptos_routes()` from generating a route for it, allowing a custom handler. These need to be in Axum path format
+/// to this function will stop `.leptos_routes()` from generating a route for it, allowing a custom handler. See [`RouteExclusion`]
+/// for the ways a route can be excluded.
 #[tracing::instrument(level = "trace", fields(error), skip_all)]
 pub fn generate_route_list_with_exclusions(
     app_fn: AppFunction,
-    excluded_routes: Option<Vec<String>>,
+    excluded_routes: impl IntoIterator<Item = impl Into<RouteExclusion>>,
 ) -> PavexRouteList {
     generate_route_list_with_exclusions_and_ssg(app_fn, excluded_routes).0
 }
@@ -334,25 +518,63 @@ pub fn generate_route_list_with_exclusions(
 /// Generates a list of all routes defined in Leptos's Router in your app. We can then use this to automatically
 /// create routes in Axum's Router without having to use wildcard matching or fallbacks. Takes in your root app Element
 /// as an argument, so it can walk you app tree. This version is tailored to generate Axum compatible paths. Adding excluded_routes
-/// to this function will stop `.leptos_routes()` from generating a route for it, allowing a custom handler. These need to be in Axum path format
+/// to this function will stop `.leptos_routes()` from generating a route for it, allowing a custom handler. See [`RouteExclusion`]
+/// for the ways a route can be excluded.
 #[tracing::instrument(level = "trace", fields(error), skip_all)]
 pub fn generate_route_list_with_exclusions_and_ssg(
     app_fn: AppFunction,
-    excluded_routes: Option<Vec<String>>,
+    excluded_routes: impl IntoIterator<Item = impl Into<RouteExclusion>>,
 ) -> (PavexRouteList, StaticDataMap) {
     generate_route_list_with_exclusions_and_ssg_and_context(app_fn, excluded_routes, || {})
 }
 
+/// Generates the route list for your app, with the static data for its statically rendered routes.
+/// Takes the same context and app constructors your Pavex routes use, and calls them with a mock
+/// request for `/`, so you don't have to build one yourself just to walk the app tree.
+#[tracing::instrument(level = "trace", fields(error), skip_all)]
+pub fn generate_route_list_for_app(
+    context_fn: impl Fn(&RequestHead) -> AdditionalContextComponent,
+    app_fn: impl Fn(&AdditionalContextComponent, &RequestHead) -> AppFunction,
+) -> (PavexRouteList, StaticDataMap) {
+    generate_route_list_for_app_with_exclusions(
+        context_fn,
+        app_fn,
+        std::iter::empty::<RouteExclusion>(),
+    )
+}
+
+/// Like [`generate_route_list_for_app`], but leaves out the routes matched by `excluded_routes`.
+/// See [`RouteExclusion`] for the ways a route can be excluded.
+#[tracing::instrument(level = "trace", fields(error), skip_all)]
+pub fn generate_route_list_for_app_with_exclusions(
+    context_fn: impl Fn(&RequestHead) -> AdditionalContextComponent,
+    app_fn: impl Fn(&AdditionalContextComponent, &RequestHead) -> AppFunction,
+    excluded_routes: impl IntoIterator<Item = impl Into<RouteExclusion>>,
+) -> (PavexRouteList, StaticDataMap) {
+    let mock_req_head =
+        static_routes::static_request_head("/").expect("`/` is a valid request path");
+    let context = context_fn(&mock_req_head);
+    generate_route_list_with_exclusions_and_ssg(app_fn(&context, &mock_req_head), excluded_routes)
+}
+
 /// Pavex Route List
 pub type PavexRouteList = Vec<PavexRouteListing>;
 
-#[derive(Clone, Debug, Default)]
-/// A route that this application can serve.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(
+    into = "route_artifact::SerializedRouteListing",
+    from = "route_artifact::SerializedRouteListing"
+)]
+/// A route that this application can serve. It can be serialized, see [`route_artifact`] for
+/// writing the route list as a build artifact.
 pub struct PavexRouteListing {
     path: String,
     mode: SsrMode,
     methods: Vec<leptos_router::Method>,
     static_mode: Option<(StaticMode, StaticDataMap)>,
+    // The paths of a static route, resolved from its static data when the route list was written
+    // as an artifact, since the static data itself can't be serialized
+    static_paths: Option<Vec<String>>,
 }
 
 impl From<RouteListing> for PavexRouteListing {
@@ -371,6 +593,7 @@ impl From<RouteListing> for PavexRouteListing {
             mode,
             methods,
             static_mode,
+            static_paths: None,
         }
     }
 }
@@ -388,6 +611,7 @@ impl PavexRouteListing {
             mode,
             methods: methods.into_iter().collect(),
             static_mode,
+            static_paths: None,
         }
     }
 
@@ -413,6 +637,192 @@ impl PavexRouteListing {
     }
 }
 
+/// A [`PavexRouteList`] indexed by the Pavex path of each route, so `render_route` can find the
+/// listing for the route Pavex matched without searching the whole list. Build it once from the
+/// generated route list and register it as a prebuilt type in your Blueprint.
+///
+/// It also holds the app's settings, and the incremental pages it has stored. A Blueprint with
+/// more than one app gives each one its own index, wrapped in a type of its own, see
+/// [`LeptosAppHandlers`](leptos_routes::LeptosAppHandlers).
+#[derive(Clone, Debug, Default)]
+pub struct PavexRouteIndex {
+    routes: Arc<HashMap<String, PavexRouteListing>>,
+    app_renderer: Option<AppRenderer>,
+    render_deadline: Option<RenderDeadline>,
+    route_render_deadlines: HashMap<String, RenderDeadline>,
+    base_path: BasePath,
+    server_fns: ServerFnRegistry,
+    body_limit: Option<BodySizeLimit>,
+    server_fn_body_limits: HashMap<&'static str, BodySizeLimit>,
+    incremental_pages: IncrementalPages,
+    page_cache: Option<PageCache>,
+    panic_view: Option<PanicView>,
+    chunk_buffering: ChunkBuffering,
+    #[cfg(feature = "compression")]
+    compression: Option<compression::CompressionOptions>,
+}
+
+impl PavexRouteIndex {
+    /// Find the route listing for a Pavex path template, e.g. `/post/:id`. A path that only
+    /// differs by a trailing slash finds the same listing, since it's the same route when it's
+    /// registered with [`TrailingSlash::Both`](leptos_routes::TrailingSlash::Both).
+    pub fn get(&self, path: &str) -> Option<&PavexRouteListing> {
+        self.routes.get(path).or_else(|| {
+            let other_path = leptos_routes::toggle_trailing_slash(path)?;
+            self.routes.get(&other_path)
+        })
+    }
+
+    /// All the routes in this index, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &PavexRouteListing> + '_ {
+        self.routes.values()
+    }
+
+    /// Build the app with these constructors when it has to be rendered outside of the request
+    /// Pavex built it for. Incremental pages are only stored once this is set.
+    pub fn with_app_renderer(mut self, renderer: AppRenderer) -> Self {
+        self.app_renderer = Some(renderer);
+        self
+    }
+
+    pub(crate) fn app_renderer(&self) -> Option<&AppRenderer> {
+        self.app_renderer.as_ref()
+    }
+
+    /// Set the render deadline for every async and in-order route. By default there is none.
+    pub fn with_render_deadline(mut self, deadline: RenderDeadline) -> Self {
+        self.render_deadline = Some(deadline);
+        self
+    }
+
+    /// Set the render deadline for a single route, overriding the default. The route is given as
+    /// its Pavex path, e.g. `/post/:id`.
+    pub fn with_route_render_deadline(
+        mut self,
+        route: impl Into<String>,
+        deadline: RenderDeadline,
+    ) -> Self {
+        self.route_render_deadlines.insert(route.into(), deadline);
+        self
+    }
+
+    /// Serve the app's server functions and assets under this base path. It has to be the same one
+    /// the routes were generated with, see [`BasePath`].
+    pub fn with_base_path(mut self, base_path: impl Into<BasePath>) -> Self {
+        self.base_path = base_path.into();
+        self
+    }
+
+    /// The base path the app is mounted under.
+    pub fn base_path(&self) -> &BasePath {
+        &self.base_path
+    }
+
+    /// Only run the server functions that belong to this app. By default it runs all of them.
+    pub fn with_server_fns(mut self, server_fns: ServerFnRegistry) -> Self {
+        self.server_fns = server_fns;
+        self
+    }
+
+    /// The server functions this app runs.
+    pub fn server_fns(&self) -> &ServerFnRegistry {
+        &self.server_fns
+    }
+
+    /// Set the most a server function request body may hold, for every server function that
+    /// doesn't set its own limit. Requests over it are answered with `413 Payload Too Large` and a
+    /// serialized `ServerFnError`. By default there is no limit.
+    pub fn with_body_limit(mut self, limit: BodySizeLimit) -> Self {
+        self.body_limit = Some(limit);
+        self
+    }
+
+    /// Set the body limit of the server function `T`, overriding the default and any limit set
+    /// with `#[server(body_limit = "...")]`.
+    pub fn with_server_fn_body_limit<T: ServerFn>(mut self, limit: BodySizeLimit) -> Self {
+        self.server_fn_body_limits.insert(T::PATH, limit);
+        self
+    }
+
+    /// The body limit of the server function at `path`. A limit set with
+    /// [`with_server_fn_body_limit`](Self::with_server_fn_body_limit) comes first, then one
+    /// declared with `#[server(body_limit = "...")]`, then the app's default.
+    pub fn body_limit_for(&self, path: &str) -> BodySizeLimit {
+        self.server_fn_body_limits
+            .get(path)
+            .copied()
+            .or_else(|| server_fn::declared_body_limit(path))
+            .or(self.body_limit)
+            .unwrap_or(BodySizeLimit::Disabled)
+    }
+
+    /// Cache the app's rendered pages in memory, see [`PageCache`]. It's off by default.
+    pub fn with_page_cache(mut self, cache: PageCache) -> Self {
+        self.page_cache = Some(cache);
+        self
+    }
+
+    /// The app's page cache, if it has one.
+    pub fn page_cache(&self) -> Option<&PageCache> {
+        self.page_cache.as_ref()
+    }
+
+    /// Set the view rendered with a `500 Internal Server Error` when the app panics before any of
+    /// the page has been sent. By default it's a plain error page.
+    pub fn with_panic_view<IV>(mut self, view_fn: impl Fn() -> IV + Send + Sync + 'static) -> Self
+    where
+        IV: IntoView + 'static,
+    {
+        self.panic_view = Some(PanicView::new(view_fn));
+        self
+    }
+
+    /// Change how the app's rendered HTML is batched into frames.
+    pub fn with_chunk_buffering(mut self, buffering: ChunkBuffering) -> Self {
+        self.chunk_buffering = buffering;
+        self
+    }
+
+    pub(crate) fn chunk_buffering(&self) -> ChunkBuffering {
+        self.chunk_buffering
+    }
+
+    /// Compress the app's rendered pages and server function responses. It's off by default.
+    #[cfg(feature = "compression")]
+    pub fn with_compression(mut self, options: compression::CompressionOptions) -> Self {
+        self.compression = Some(options);
+        self
+    }
+
+    /// How the app's responses are compressed, if they are.
+    #[cfg(feature = "compression")]
+    pub fn compression(&self) -> Option<&compression::CompressionOptions> {
+        self.compression.as_ref()
+    }
+
+    /// The render deadline for a route, if one has been set.
+    pub(crate) fn render_deadline(&self, route: &str) -> Option<&RenderDeadline> {
+        self.route_render_deadlines
+            .get(route)
+            .or(self.render_deadline.as_ref())
+    }
+}
+
+impl From<PavexRouteList> for PavexRouteIndex {
+    fn from(routes: PavexRouteList) -> Self {
+        let routes = Arc::new(
+            routes
+                .into_iter()
+                .map(|listing| (listing.path().to_string(), listing))
+                .collect(),
+        );
+        Self {
+            routes,
+            ..Default::default()
+        }
+    }
+}
+
 trait PavexPath {
     fn to_pavex_path(&self) -> String;
 }
@@ -421,7 +831,8 @@ impl PavexPath for &[PathSegment] {
     fn to_pavex_path(&self) -> String {
         let mut path = String::new();
         for segment in self.iter() {
-            // TODO trailing slash handling
+            // Trailing slashes are kept as Leptos defines them, `TrailingSlash` decides what
+            // happens to the other form when the routes are registered
             let raw = segment.as_raw_str();
             if !raw.is_empty() && !raw.starts_with('/') {
                 path.push('/');
@@ -446,12 +857,12 @@ impl PavexPath for &[PathSegment] {
 /// Generates a list of all routes defined in Leptos's Router in your app. We can then use this to automatically
 /// create routes in Axum's Router without having to use wildcard matching or fallbacks. Take in your root app Element
 /// as an argument, so it can walk your app tree. This version is tailored to generate Axum compatible paths. Adding excluded_routes
-/// to this function will stop `.leptos_routes()` from generating a route for it, allowing a custom handler. These need to be in Axum path format
-/// Additional context will be provided to the app Element.
+/// to this function will stop `.leptos_routes()` from generating a route for it, allowing a custom handler. See [`RouteExclusion`]
+/// for the ways a route can be excluded. Additional context will be provided to the app Element.
 #[tracing::instrument(level = "trace", fields(error), skip_all)]
 pub fn generate_route_list_with_exclusions_and_ssg_and_context(
     app_fn: AppFunction,
-    excluded_routes: Option<Vec<String>>,
+    excluded_routes: impl IntoIterator<Item = impl Into<RouteExclusion>>,
     additional_context: impl Fn() + 'static + Clone,
 ) -> (PavexRouteList, StaticDataMap) {
     init_executor();
@@ -465,8 +876,7 @@ pub fn generate_route_list_with_exclusions_and_ssg_and_context(
 
             provide_post_contexts("", &Default::default(), mock_parts, Default::default());
             additional_context();
-            let foo = RouteList::generate(move || app_fn.inner());
-            foo
+            RouteList::generate(move || app_fn.inner())
         })
         .unwrap();
 
@@ -487,13 +897,16 @@ pub fn generate_route_list_with_exclusions_and_ssg_and_context(
             )]
         } else {
             // Routes to exclude from auto generation
-            if let Some(excluded_routes) = excluded_routes {
-                routes.retain(|p| !excluded_routes.iter().any(|e| e == p.path()))
-            }
+            let excluded_routes = excluded_routes
+                .into_iter()
+                .map(Into::into)
+                .collect::<Vec<_>>();
+            route_exclusion::exclude_routes(&mut routes, &excluded_routes);
             routes
         },
-        StaticDataMap::new(), // TODO
-                              //static_data_map,
+        // The Leptos revision this is built against has no static data to hand out, static routes
+        // with params get theirs from `StaticParams` instead
+        StaticDataMap::new(),
     )
 }
 
@@ -514,7 +927,7 @@ pub fn pass_leptos_context(
         RouteType::ServerFn => Owner::new(),
         RouteType::Component => Owner::new_root(Some(Arc::new(SsrSharedContext::new()))),
     };
-    let req_parts = RequestParts::new_from_req(&req_head);
+    let req_parts = RequestParts::new_from_req(req_head);
     // Set the created Owner as the current one, by setting the thread local. Pavex pins each request to their own
     // thread, so this should be fineTM
     owner.with(|| {
@@ -523,3 +936,49 @@ pub fn pass_leptos_context(
     });
     owner
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::borrow::Cow;
+
+    fn listing(segments: Vec<PathSegment>) -> PavexRouteListing {
+        PavexRouteListing::from(RouteListing::from_path(segments))
+    }
+
+    #[test]
+    fn leptos_paths_become_pavex_paths() {
+        let post = listing(vec![
+            PathSegment::Static(Cow::Borrowed("post")),
+            PathSegment::Param(Cow::Borrowed("id")),
+        ]);
+        assert_eq!(post.path(), "/post/:id");
+        let docs = listing(vec![
+            PathSegment::Static(Cow::Borrowed("/docs")),
+            PathSegment::Splat(Cow::Borrowed("rest")),
+        ]);
+        assert_eq!(docs.path(), "/docs/*rest");
+        assert_eq!(listing(vec![PathSegment::Unit]).path(), "/");
+        assert_eq!(listing(Vec::new()).path(), "/");
+    }
+
+    #[test]
+    fn trailing_slashes_are_kept() {
+        let about = listing(vec![PathSegment::Static(Cow::Borrowed("about/"))]);
+        assert_eq!(about.path(), "/about/");
+    }
+
+    #[test]
+    fn the_index_finds_listings_by_their_pavex_path() {
+        let index = PavexRouteIndex::from(vec![
+            listing(vec![PathSegment::Static(Cow::Borrowed("about"))]),
+            listing(vec![
+                PathSegment::Static(Cow::Borrowed("post")),
+                PathSegment::Param(Cow::Borrowed("id")),
+            ]),
+        ]);
+        assert_eq!(index.get("/post/:id").map(|l| l.path()), Some("/post/:id"));
+        assert_eq!(index.get("/about/").map(|l| l.path()), Some("/about"));
+        assert!(index.get("/post/:slug").is_none());
+    }
+}
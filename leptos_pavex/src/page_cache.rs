@@ -0,0 +1,570 @@
+use crate::PavexRouteIndex;
+use bytes::{Bytes, BytesMut};
+use http_body::Frame;
+use parking_lot::Mutex;
+use pavex::http::header::{AUTHORIZATION, CACHE_CONTROL, COOKIE, SET_COOKIE};
+use pavex::http::{HeaderMap, HeaderName, Method, StatusCode};
+use pavex::middleware::Next;
+use pavex::request::path::MatchedPathPattern;
+use pavex::request::RequestHead;
+use pavex::response::body::raw::{Full, RawBody};
+use pavex::response::Response;
+use pin_project::pin_project;
+use std::collections::HashMap;
+use std::future::IntoFuture;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+/// Settings for a [`PageCache`].
+#[derive(Clone, Debug)]
+pub struct PageCacheOptions {
+    /// How long a rendered page is served from the cache.
+    pub ttl: Duration,
+    /// The most bytes of HTML the cache will hold. The oldest pages are evicted to make room.
+    pub max_bytes: usize,
+    /// Request headers whose values are part of the cache key, e.g. `Accept-Language`.
+    pub vary_headers: Vec<HeaderName>,
+    /// Cookies whose values are part of the cache key, e.g. a theme or locale cookie.
+    pub vary_cookies: Vec<String>,
+}
+
+impl Default for PageCacheOptions {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(60),
+            max_bytes: 64 * 1024 * 1024,
+            vary_headers: Vec::new(),
+            vary_cookies: Vec::new(),
+        }
+    }
+}
+
+/// An in-memory cache of rendered pages, keyed on the matched route, the path and query, and the
+/// configured vary headers and cookies. Only successful `GET` responses that don't set cookies,
+/// and aren't marked `Cache-Control: private` or `no-store`, are stored. Requests with an
+/// `Authorization` header, or with a cookie that isn't one of the vary cookies, may get a page
+/// made for them alone, so they skip the cache.
+///
+/// The cache is opt-in: attach it to the app's route index with
+/// [`PavexRouteIndex::with_page_cache`], and register the [`serve_cached_page`] middleware that
+/// serves pages from it:
+///
+/// ```rust,ignore
+/// bp.wrap(f!(leptos_pavex::page_cache::serve_cached_page));
+/// ```
+///
+/// It's provided as context to the app's pages and server functions, so a server function that
+/// changes data can `use_context::<PageCache>()` to purge the pages that show it.
+#[derive(Clone, Debug)]
+pub struct PageCache(Arc<PageCacheInner>);
+
+#[derive(Debug)]
+struct PageCacheInner {
+    options: PageCacheOptions,
+    pages: Mutex<CachedPages>,
+}
+
+#[derive(Debug, Default)]
+struct CachedPages {
+    entries: HashMap<String, CachedPage>,
+    total_bytes: usize,
+}
+
+#[cfg(feature = "ssr")]
+tokio::task_local! {
+    /// Set by [`serve_cached_page`] while it renders a page it's going to store
+    static INCOMPLETE_BODY: IncompleteBody;
+}
+
+/// Raised by the renderer when the body it streams won't be the complete page, because the app
+/// panicked or the render deadline passed after the response had started. The status has been
+/// sent by then, so this is the only way the cache can tell the page apart from a good one.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct IncompleteBody(Arc<AtomicBool>);
+
+impl IncompleteBody {
+    /// The flag for the page being rendered, if the cache is going to store it.
+    pub(crate) fn current() -> Option<Self> {
+        #[cfg(feature = "ssr")]
+        {
+            INCOMPLETE_BODY.try_with(Clone::clone).ok()
+        }
+        #[cfg(not(feature = "ssr"))]
+        {
+            None
+        }
+    }
+
+    pub(crate) fn raise(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    fn is_raised(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CachedPage {
+    route: String,
+    path: String,
+    stored_at: Instant,
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl PageCache {
+    pub fn new(options: PageCacheOptions) -> Self {
+        Self(Arc::new(PageCacheInner {
+            options,
+            pages: Default::default(),
+        }))
+    }
+
+    /// Remove every page from the cache.
+    pub fn purge_all(&self) {
+        let mut pages = self.0.pages.lock();
+        pages.entries.clear();
+        pages.total_bytes = 0;
+    }
+
+    /// Remove every page rendered for a route, given as its Pavex path, e.g. `/post/:id`.
+    pub fn purge_route(&self, route: &str) {
+        self.purge_where(|page| page.route == route);
+    }
+
+    /// Remove every page rendered for a request path, e.g. `/post/12`, whatever its query string.
+    pub fn purge_path(&self, path: &str) {
+        self.purge_where(|page| page.path == path);
+    }
+
+    fn purge_where(&self, predicate: impl Fn(&CachedPage) -> bool) {
+        let mut pages = self.0.pages.lock();
+        let mut freed = 0;
+        pages.entries.retain(|_, page| {
+            let purge = predicate(page);
+            if purge {
+                freed += page.body.len();
+            }
+            !purge
+        });
+        pages.total_bytes -= freed;
+    }
+
+    /// The cache key for a request to `route`, or `None` if the request can't be cached. Every
+    /// value of a vary header is kept apart, so `a, b` and `ab` don't share a page.
+    pub(crate) fn key_for(&self, route: &str, req_head: &RequestHead) -> Option<String> {
+        if req_head.method != Method::GET || req_head.headers.contains_key(AUTHORIZATION) {
+            return None;
+        }
+        let path_and_query = req_head
+            .target
+            .path_and_query()
+            .map(|pq| pq.as_str())
+            .unwrap_or("/");
+        let mut key = format!("{route}\n{path_and_query}");
+        for name in self.0.options.vary_headers.iter() {
+            key.push('\n');
+            for (i, value) in req_head.headers.get_all(name).iter().enumerate() {
+                if i > 0 {
+                    key.push_str(", ");
+                }
+                key.push_str(&String::from_utf8_lossy(value.as_bytes()));
+            }
+        }
+        let cookies = req_head
+            .headers
+            .get_all(COOKIE)
+            .iter()
+            .filter_map(|h| h.to_str().ok())
+            .flat_map(|h| h.split(';'))
+            .filter_map(|c| c.trim().split_once('='))
+            .collect::<Vec<_>>();
+        // Any other cookie could be a session the page is rendered for
+        let vary_cookies = &self.0.options.vary_cookies;
+        if cookies
+            .iter()
+            .any(|(name, _)| !vary_cookies.iter().any(|vary| vary == name))
+        {
+            return None;
+        }
+        for name in self.0.options.vary_cookies.iter() {
+            key.push('\n');
+            if let Some((_, value)) = cookies.iter().find(|(n, _)| n == name) {
+                key.push_str(value);
+            }
+        }
+        Some(key)
+    }
+
+    /// Returns the cached response for this key, if there's one that hasn't expired.
+    pub(crate) fn get(&self, key: &str) -> Option<Response> {
+        let mut pages = self.0.pages.lock();
+        let page = pages.entries.get(key)?;
+        if page.stored_at.elapsed() >= self.0.options.ttl {
+            let size = page.body.len();
+            pages.entries.remove(key);
+            pages.total_bytes -= size;
+            return None;
+        }
+        let mut res = Response::new(page.status).set_raw_body(Full::new(page.body.clone()));
+        *res.headers_mut() = page.headers.clone();
+        Some(res)
+    }
+
+    /// Wrap the body of a freshly rendered response, so it gets stored once it has been
+    /// streamed to the client in full, unless `incomplete` has been raised by then.
+    pub(crate) fn store(
+        &self,
+        key: String,
+        route: &str,
+        path: &str,
+        res: Response,
+        incomplete: IncompleteBody,
+    ) -> Response {
+        if res.status() != StatusCode::OK
+            || res.headers().contains_key(SET_COOKIE)
+            || is_private(res.headers())
+        {
+            return res;
+        }
+        let status = res.status();
+        let headers = res.headers().clone();
+        let (_, body) = res.into_parts();
+
+        let body = CachingBody {
+            inner: body,
+            buffer: BytesMut::new(),
+            incomplete,
+            entry: Some((
+                self.clone(),
+                key,
+                CachedPage {
+                    route: route.to_string(),
+                    path: path.to_string(),
+                    stored_at: Instant::now(),
+                    status,
+                    headers: headers.clone(),
+                    body: Bytes::new(),
+                },
+            )),
+        };
+        let mut res = Response::new(status).set_raw_body(body);
+        *res.headers_mut() = headers;
+        res
+    }
+
+    fn insert(&self, key: String, page: CachedPage) {
+        let max_bytes = self.0.options.max_bytes;
+        if page.body.len() > max_bytes {
+            return;
+        }
+        let mut pages = self.0.pages.lock();
+        if let Some(old) = pages.entries.remove(&key) {
+            pages.total_bytes -= old.body.len();
+        }
+        // Evict the oldest pages until the new one fits
+        while pages.total_bytes + page.body.len() > max_bytes {
+            let Some(oldest) = pages
+                .entries
+                .iter()
+                .min_by_key(|(_, page)| page.stored_at)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            if let Some(evicted) = pages.entries.remove(&oldest) {
+                pages.total_bytes -= evicted.body.len();
+            }
+        }
+        pages.total_bytes += page.body.len();
+        pages.entries.insert(key, page);
+    }
+}
+
+/// Wrapping middleware that serves Leptos pages from the [`PageCache`], and stores the ones that
+/// get rendered. Register it with `bp.wrap` before the Leptos routes. It runs before any of the
+/// handler's constructors, so a cached page is served without building the Leptos context or the
+/// app. Routes that aren't Leptos pages, statically rendered ones, and the routes of apps without
+/// a cache are passed through.
+pub async fn serve_cached_page<C>(
+    next: Next<C>,
+    routes: &PavexRouteIndex,
+    req_head: &RequestHead,
+    matched_path: &MatchedPathPattern,
+) -> Response
+where
+    C: IntoFuture<Output = Response>,
+{
+    let Some(cache) = routes.page_cache() else {
+        return next.await;
+    };
+    let Some(listing) = routes.get(matched_path.inner()) else {
+        return next.await;
+    };
+    if listing.static_mode().is_some() {
+        return next.await;
+    }
+    let Some(key) = cache.key_for(listing.path(), req_head) else {
+        return next.await;
+    };
+    // Compressed pages are stored as they were sent, so each encoding gets its own copy
+    #[cfg(feature = "compression")]
+    let key = match crate::compression::negotiate(routes.compression(), &req_head.headers) {
+        Some(encoding) => format!("{key}\n{}", encoding.as_str()),
+        None => key,
+    };
+    if let Some(res) = cache.get(&key) {
+        return res;
+    }
+    let incomplete = IncompleteBody::default();
+    #[cfg(feature = "ssr")]
+    let res = INCOMPLETE_BODY
+        .scope(incomplete.clone(), next.into_future())
+        .await;
+    #[cfg(not(feature = "ssr"))]
+    let res = next.await;
+    cache.store(key, listing.path(), req_head.target.path(), res, incomplete)
+}
+
+/// Whether the response asked not to be stored in a shared cache.
+pub(crate) fn is_private(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(CACHE_CONTROL)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|directive| directive.trim().split('=').next().unwrap_or_default())
+        .any(|directive| {
+            directive.eq_ignore_ascii_case("private") || directive.eq_ignore_ascii_case("no-store")
+        })
+}
+
+/// Passes the frames of a response body through, keeping a copy of the data. When the body is
+/// finished, the copy is stored in the cache.
+#[pin_project]
+struct CachingBody<B> {
+    #[pin]
+    inner: B,
+    buffer: BytesMut,
+    incomplete: IncompleteBody,
+    entry: Option<(PageCache, String, CachedPage)>,
+}
+
+impl<B> RawBody for CachingBody<B>
+where
+    B: RawBody<Data = Bytes>,
+{
+    type Data = Bytes;
+
+    type Error = B::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let this = self.project();
+        let frame = this.inner.poll_frame(cx);
+        match &frame {
+            Poll::Ready(Some(Ok(frame))) => {
+                if let Some(data) = frame.data_ref() {
+                    this.buffer.extend_from_slice(data);
+                }
+            }
+            Poll::Ready(None) => {
+                if let Some((cache, key, mut page)) = this.entry.take() {
+                    if !this.incomplete.is_raised() {
+                        page.body = std::mem::take(this.buffer).freeze();
+                        cache.insert(key, page);
+                    }
+                }
+            }
+            // Don't store bodies that failed halfway through
+            Poll::Ready(Some(Err(_))) => {
+                this.entry.take();
+            }
+            Poll::Pending => {}
+        }
+        frame
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pavex::http::HeaderValue;
+
+    fn request(path: &str, headers: &[(&str, &str)]) -> RequestHead {
+        let mut request = http::Request::builder().uri(path);
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        request.body(()).unwrap().into_parts().0.into()
+    }
+
+    fn page(route: &str, path: &str, body: &'static str) -> CachedPage {
+        CachedPage {
+            route: route.to_string(),
+            path: path.to_string(),
+            stored_at: Instant::now(),
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body: Bytes::from_static(body.as_bytes()),
+        }
+    }
+
+    #[test]
+    fn expired_pages_are_not_served() {
+        let cache = PageCache::new(PageCacheOptions {
+            ttl: Duration::ZERO,
+            ..Default::default()
+        });
+        cache.insert("key".to_string(), page("/", "/", "home"));
+        assert!(cache.get("key").is_none());
+        assert_eq!(cache.0.pages.lock().total_bytes, 0);
+    }
+
+    #[test]
+    fn fresh_pages_are_served() {
+        let cache = PageCache::new(PageCacheOptions::default());
+        cache.insert("key".to_string(), page("/", "/", "home"));
+        assert_eq!(cache.get("key").unwrap().status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn oldest_pages_are_evicted_to_stay_under_the_size_bound() {
+        let cache = PageCache::new(PageCacheOptions {
+            max_bytes: 8,
+            ..Default::default()
+        });
+        cache.insert("first".to_string(), page("/", "/first", "12345"));
+        cache.insert("second".to_string(), page("/", "/second", "12345"));
+        assert!(cache.get("first").is_none());
+        assert!(cache.get("second").is_some());
+        assert_eq!(cache.0.pages.lock().total_bytes, 5);
+
+        cache.insert("too_big".to_string(), page("/", "/big", "123456789"));
+        assert!(cache.get("too_big").is_none());
+        assert!(cache.get("second").is_some());
+    }
+
+    #[test]
+    fn vary_headers_and_cookies_are_part_of_the_key() {
+        let cache = PageCache::new(PageCacheOptions {
+            vary_headers: vec![HeaderName::from_static("accept-language")],
+            vary_cookies: vec!["theme".to_string()],
+            ..Default::default()
+        });
+        let key = |headers: &[(&str, &str)]| cache.key_for("/", &request("/", headers)).unwrap();
+
+        assert_ne!(
+            key(&[("accept-language", "en")]),
+            key(&[("accept-language", "fr")])
+        );
+        assert_ne!(
+            key(&[("cookie", "theme=dark")]),
+            key(&[("cookie", "theme=light")])
+        );
+        assert_ne!(
+            key(&[("accept-language", "a"), ("accept-language", "b")]),
+            key(&[("accept-language", "ab")])
+        );
+    }
+
+    #[test]
+    fn only_get_requests_have_a_key() {
+        let cache = PageCache::new(PageCacheOptions::default());
+        let mut req_head = request("/", &[]);
+        req_head.method = Method::POST;
+        assert!(cache.key_for("/", &req_head).is_none());
+    }
+
+    #[test]
+    fn requests_for_a_single_visitor_skip_the_cache() {
+        let cache = PageCache::new(PageCacheOptions {
+            vary_cookies: vec!["theme".to_string()],
+            ..Default::default()
+        });
+        let key = |headers: &[(&str, &str)]| cache.key_for("/", &request("/", headers));
+
+        assert!(key(&[("authorization", "Bearer token")]).is_none());
+        assert!(key(&[("cookie", "session=1")]).is_none());
+        assert!(key(&[("cookie", "theme=dark; session=1")]).is_none());
+        assert!(key(&[("cookie", "theme=dark")]).is_some());
+        assert!(key(&[]).is_some());
+    }
+
+    #[test]
+    fn the_cache_is_provided_to_the_app() {
+        let cache = PageCache::new(PageCacheOptions::default());
+        let routes = PavexRouteIndex::default().with_page_cache(cache.clone());
+        let owner = leptos::prelude::Owner::new();
+        let provided = owner.with(|| {
+            crate::provide_app_contexts(&routes);
+            leptos::prelude::use_context::<PageCache>()
+        });
+        assert!(provided.is_some_and(|provided| Arc::ptr_eq(&provided.0, &cache.0)));
+    }
+
+    #[test]
+    fn purging_removes_matching_pages() {
+        let cache = PageCache::new(PageCacheOptions::default());
+        cache.insert("a".to_string(), page("/post/:id", "/post/1", "a"));
+        cache.insert("b".to_string(), page("/post/:id", "/post/2", "b"));
+        cache.insert("c".to_string(), page("/", "/", "c"));
+
+        cache.purge_path("/post/1");
+        assert!(cache.get("a").is_none());
+        assert!(cache.get("b").is_some());
+
+        cache.purge_route("/post/:id");
+        assert!(cache.get("b").is_none());
+        assert!(cache.get("c").is_some());
+
+        cache.purge_all();
+        assert!(cache.get("c").is_none());
+        assert_eq!(cache.0.pages.lock().total_bytes, 0);
+    }
+
+    fn stream_to_end(res: Response) {
+        let (_, body) = res.into_parts();
+        futures::executor::block_on(http_body_util::BodyExt::collect(body)).unwrap();
+    }
+
+    #[test]
+    fn complete_bodies_are_stored() {
+        let cache = PageCache::new(PageCacheOptions::default());
+        let res = Response::ok().set_raw_body(Full::new(Bytes::from_static(b"page")));
+        stream_to_end(cache.store("key".to_string(), "/", "/", res, Default::default()));
+        assert!(cache.get("key").is_some());
+    }
+
+    #[test]
+    fn incomplete_bodies_are_not_stored() {
+        let cache = PageCache::new(PageCacheOptions::default());
+        let incomplete = IncompleteBody::default();
+        let res = Response::ok().set_raw_body(Full::new(Bytes::from_static(b"page")));
+        let res = cache.store("key".to_string(), "/", "/", res, incomplete.clone());
+        incomplete.raise();
+        stream_to_end(res);
+        assert!(cache.get("key").is_none());
+    }
+
+    #[test]
+    fn private_responses_are_not_stored() {
+        let headers = |value: &'static str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(CACHE_CONTROL, HeaderValue::from_static(value));
+            headers
+        };
+        assert!(is_private(&headers("private")));
+        assert!(is_private(&headers("no-store")));
+        assert!(is_private(&headers("max-age=60, Private")));
+        assert!(!is_private(&headers("max-age=60")));
+        assert!(!is_private(&HeaderMap::new()));
+    }
+}
@@ -0,0 +1,72 @@
+use crate::PavexRouteIndex;
+use leptos::prelude::use_context;
+use leptos::IntoView;
+use leptos_router::location::RequestUrl;
+use reactive_graph::owner::Owner;
+use std::any::Any;
+use std::fmt;
+use std::sync::Arc;
+
+/// The view rendered with a `500 Internal Server Error` when the app panics before any of the
+/// page has been sent, see [`PavexRouteIndex::with_panic_view`].
+#[derive(Clone)]
+pub(crate) struct PanicView(Arc<dyn Fn() -> String + Send + Sync>);
+
+impl PanicView {
+    pub(crate) fn new<IV>(view_fn: impl Fn() -> IV + Send + Sync + 'static) -> Self
+    where
+        IV: IntoView + 'static,
+    {
+        Self(Arc::new(move || Owner::new().with(|| view_fn().to_html())))
+    }
+}
+
+impl fmt::Debug for PanicView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PanicView").finish_non_exhaustive()
+    }
+}
+
+/// Appended to a response that had already started streaming when the app panicked, so the
+/// truncated document can be told apart from a complete one.
+pub(crate) const PANIC_MARKER: &str = "<!--leptos-pavex-panic--><script>console.error(\
+    \"The server panicked while rendering this page, the rest of it is missing.\")</script>";
+
+/// The page for a panic in the app with these routes, or a plain one if it didn't set a view.
+pub(crate) fn panic_page(routes: Option<&PavexRouteIndex>) -> String {
+    match routes.and_then(|routes| routes.panic_view.as_ref()) {
+        Some(view) => (view.0)(),
+        None => "<!DOCTYPE html><html><head><title>Internal Server Error</title></head>\
+                 <body><h1>Internal Server Error</h1></body></html>"
+            .to_string(),
+    }
+}
+
+/// Log a panic caught while rendering, with the path that was being rendered.
+pub(crate) fn log_panic(owner: &Owner, payload: &(dyn Any + Send)) {
+    let path = owner
+        .with(use_context::<RequestUrl>)
+        .map(|url| url.as_ref().to_string())
+        .unwrap_or_default();
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("Box<dyn Any>");
+    tracing::error!(path = %path, "The app panicked while rendering: {message}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use leptos::prelude::*;
+
+    #[test]
+    fn apps_get_their_own_panic_page() {
+        let routes =
+            PavexRouteIndex::default().with_panic_view(|| view! { <h1>"Something broke"</h1> });
+        assert!(panic_page(Some(&routes)).contains("<h1>Something broke</h1>"));
+        assert!(panic_page(Some(&PavexRouteIndex::default())).contains("Internal Server Error"));
+        assert!(panic_page(None).contains("Internal Server Error"));
+    }
+}
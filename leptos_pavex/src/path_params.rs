@@ -0,0 +1,155 @@
+use leptos::prelude::use_context;
+use leptos::server_fn::error::ServerFnError;
+use pavex::request::path::RawPathParams;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// The path params Pavex matched for the current request, percent-decoded. `render_route`
+/// provides them in Leptos context, so server-side code can read them with [`use_path_params`]
+/// without going through the Leptos router. The names are the ones in the Pavex path, e.g. `id` for
+/// `/post/:id`.
+///
+/// Server function routes have no params, so a server function only sees them when it's called
+/// directly while a page is rendered on the server, e.g. from a resource. Called from the client,
+/// it gets no params and should take what it needs as arguments instead.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PavexPathParams(Arc<Vec<(String, String)>>);
+
+impl PavexPathParams {
+    pub fn from_raw(raw: &RawPathParams) -> Self {
+        Self(Arc::new(
+            raw.iter()
+                .map(|(name, value)| {
+                    let decoded = match value.decode() {
+                        Ok(decoded) => decoded.into_owned(),
+                        Err(_) => value.as_str().to_string(),
+                    };
+                    (name.to_string(), decoded)
+                })
+                .collect(),
+        ))
+    }
+
+    /// The value of a param, if the route has it.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Parse the value of a param into `T`.
+    pub fn parse<T>(&self, name: &str) -> Result<T, PathParamError>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        let value = self
+            .get(name)
+            .ok_or_else(|| PathParamError::Missing(name.to_string()))?;
+        value.parse().map_err(|e: T::Err| PathParamError::Invalid {
+            name: name.to_string(),
+            value: value.to_string(),
+            message: e.to_string(),
+        })
+    }
+
+    /// All the params, in the order they appear in the path.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> + '_ {
+        self.0
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+}
+
+/// A path param that couldn't be read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PathParamError {
+    /// The matched route has no param with this name.
+    Missing(String),
+    /// The param's value couldn't be parsed into the requested type.
+    Invalid {
+        name: String,
+        value: String,
+        message: String,
+    },
+}
+
+impl fmt::Display for PathParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathParamError::Missing(name) => write!(f, "The path has no `{name}` param"),
+            PathParamError::Invalid {
+                name,
+                value,
+                message,
+            } => write!(f, "The `{name}` path param `{value}` is invalid: {message}"),
+        }
+    }
+}
+
+impl Error for PathParamError {}
+
+/// The path params Pavex matched for the current request, if they were provided.
+pub fn use_path_params() -> Option<PavexPathParams> {
+    use_context::<PavexPathParams>()
+}
+
+/// Read a path param of the current request and parse it into `T`. The error fits a server
+/// function with any custom error type, name it if `?` can't infer it. See [`PavexPathParams`] for
+/// when server functions have params to read.
+pub fn extract_path_param<T, CustErr>(name: &str) -> Result<T, ServerFnError<CustErr>>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let params = use_path_params().ok_or_else(|| {
+        ServerFnError::ServerError(
+            "The path params weren't provided, is a page being rendered?".to_string(),
+        )
+    })?;
+    params
+        .parse(name)
+        .map_err(|e| ServerFnError::Args(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use leptos::prelude::{provide_context, Owner};
+    use leptos::server_fn::error::NoCustomError;
+
+    fn with_params<T>(params: &[(&str, &str)], f: impl FnOnce() -> T) -> T {
+        let params = params
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        Owner::new().with(|| {
+            provide_context(PavexPathParams(Arc::new(params)));
+            f()
+        })
+    }
+
+    #[test]
+    fn params_are_parsed_with_the_callers_error_type() {
+        // Any custom error type fits, here it's a plain string
+        let id = with_params(&[("id", "42")], || extract_path_param::<u32, String>("id"));
+        assert!(matches!(id, Ok(42)));
+
+        let invalid = with_params(&[("id", "x")], || extract_path_param::<u32, String>("id"));
+        assert!(matches!(invalid, Err(ServerFnError::Args(_))));
+
+        let missing = with_params(&[("id", "42")], || {
+            extract_path_param::<u32, String>("slug")
+        });
+        assert!(matches!(missing, Err(ServerFnError::Args(_))));
+    }
+
+    #[test]
+    fn params_outside_of_a_page_render_are_a_server_error() {
+        let id = Owner::new().with(|| extract_path_param::<u32, NoCustomError>("id"));
+        assert!(matches!(id, Err(ServerFnError::ServerError(_))));
+    }
+}
@@ -3,7 +3,10 @@ use leptos::{
     tachys::{renderer::dom::Dom, view::any_view::AnyView},
     IntoView,
 };
+use pavex::request::RequestHead;
 use reactive_graph::owner::Owner;
+use std::fmt;
+use std::sync::Arc;
 
 //A type to hold the result of the App Function Closure so Pavex is happy
 pub struct AppFunction(AnyView<Dom>);
@@ -60,3 +63,46 @@ impl AdditionalContextServerFn {
         &self.0
     }
 }
+
+type AppFn = Arc<dyn Fn(&AdditionalContextComponent, &RequestHead) -> AppFunction + Send + Sync>;
+
+/// Builds the context and the app outside of the constructors Pavex calls for each request. Give
+/// it the same constructors you registered in your Blueprint, and attach it to the route index with
+/// [`PavexRouteIndex::with_app_renderer`](crate::PavexRouteIndex::with_app_renderer).
+///
+/// It's used to render incremental pages, which are served to every visitor and so can't be
+/// rendered with the context built for the request that triggered them.
+#[derive(Clone)]
+pub struct AppRenderer {
+    context_fn: Arc<dyn Fn(&RequestHead) -> AdditionalContextComponent + Send + Sync>,
+    app_fn: AppFn,
+}
+
+impl AppRenderer {
+    pub fn new<C, A>(context_fn: C, app_fn: A) -> Self
+    where
+        C: Fn(&RequestHead) -> AdditionalContextComponent + Send + Sync + 'static,
+        A: Fn(&AdditionalContextComponent, &RequestHead) -> AppFunction + Send + Sync + 'static,
+    {
+        Self {
+            context_fn: Arc::new(context_fn),
+            app_fn: Arc::new(app_fn),
+        }
+    }
+
+    /// Build a fresh context and app for this request.
+    pub(crate) fn build(
+        &self,
+        req_head: &RequestHead,
+    ) -> (AdditionalContextComponent, AppFunction) {
+        let context = (self.context_fn)(req_head);
+        let app = (self.app_fn)(&context, req_head);
+        (context, app)
+    }
+}
+
+impl fmt::Debug for AppRenderer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AppRenderer").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,173 @@
+use pavex::http::HeaderValue;
+
+/// Build `Link` headers for the assets the page's `<head>` asks for: the JS and wasm linked by
+/// `<HydrationScripts/>` and the stylesheets registered with `<MetaTags/>`. They go out with the
+/// response headers, so the browser can start fetching before it has parsed any HTML. Pavex can't
+/// send a `103 Early Hints` response, but proxies that support them can turn these headers into
+/// one.
+pub(crate) fn preload_links(html: &str) -> Vec<HeaderValue> {
+    let head = match html.find("</head>") {
+        Some(end) => &html[..end],
+        None => html,
+    };
+
+    let mut links = Vec::new();
+    let mut rest = head;
+    while let Some(start) = rest.find("<link") {
+        let tag = &rest[start + "<link".len()..];
+        let Some(end) = tag.find('>') else {
+            break;
+        };
+        if let Some(link) = preload_link(&tag[..end]) {
+            if let Ok(value) = HeaderValue::from_str(&link) {
+                links.push(value);
+            }
+        }
+        rest = &tag[end..];
+    }
+    links
+}
+
+/// Turn the attributes of a `<link>` tag into a `Link` header value, if it's something worth
+/// preloading.
+fn preload_link(attributes: &str) -> Option<String> {
+    let attributes = parse_attributes(attributes);
+    let get = |name: &str| {
+        attributes
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    };
+    let href = get("href")?;
+
+    let mut link = match get("rel")? {
+        "stylesheet" => format!("<{href}>; rel=preload; as=style"),
+        "modulepreload" => format!("<{href}>; rel=modulepreload"),
+        "preload" => format!("<{href}>; rel=preload; as={}", get("as")?),
+        _ => return None,
+    };
+    if let Some(mime) = get("type") {
+        link.push_str(&format!("; type=\"{mime}\""));
+    }
+    if get("crossorigin").is_some() {
+        link.push_str("; crossorigin");
+    }
+    Some(link)
+}
+
+/// A forgiving parser for the attributes of an HTML tag. Handles double quoted, single quoted,
+/// unquoted and empty attributes.
+fn parse_attributes(tag: &str) -> Vec<(String, String)> {
+    let mut attributes = Vec::new();
+    let mut rest = tag.trim_start().trim_end_matches('/');
+    while !rest.is_empty() {
+        let name_end = rest
+            .find(|c: char| c == '=' || c.is_whitespace())
+            .unwrap_or(rest.len());
+        let name = rest[..name_end].to_string();
+        rest = rest[name_end..].trim_start();
+
+        let value = if let Some(after_eq) = rest.strip_prefix('=') {
+            let after_eq = after_eq.trim_start();
+            let (value, remaining) = match after_eq.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let inner = &after_eq[1..];
+                    let end = inner.find(quote).unwrap_or(inner.len());
+                    (&inner[..end], inner.get(end + 1..).unwrap_or(""))
+                }
+                _ => {
+                    let end = after_eq.find(char::is_whitespace).unwrap_or(after_eq.len());
+                    (&after_eq[..end], &after_eq[end..])
+                }
+            };
+            rest = remaining.trim_start();
+            value.to_string()
+        } else {
+            String::new()
+        };
+
+        if !name.is_empty() {
+            attributes.push((name, value));
+        }
+    }
+    attributes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn links(html: &str) -> Vec<String> {
+        preload_links(html)
+            .into_iter()
+            .map(|link| link.to_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn the_hydration_scripts_are_preloaded() {
+        let html = r#"<!DOCTYPE html><html><head>
+            <link rel="modulepreload" href="/pkg/app.js" nonce="abc"/>
+            <link rel="preload" href="/pkg/app.wasm" as="fetch" type="application/wasm" crossorigin=""/>
+            </head><body></body></html>"#;
+        assert_eq!(
+            links(html),
+            [
+                "</pkg/app.js>; rel=modulepreload",
+                "</pkg/app.wasm>; rel=preload; as=fetch; type=\"application/wasm\"; crossorigin",
+            ]
+        );
+    }
+
+    #[test]
+    fn stylesheets_are_preloaded_as_styles() {
+        let html = r#"<head><link rel="stylesheet" id="leptos" href="/pkg/app.css"></head>"#;
+        assert_eq!(links(html), ["</pkg/app.css>; rel=preload; as=style"]);
+    }
+
+    #[test]
+    fn other_links_are_left_out() {
+        let html = r#"<head>
+            <link rel="icon" href="/favicon.ico">
+            <link rel="canonical" href="https://example.com/">
+            <link rel="preload" href="/font.woff2">
+            <link rel="stylesheet">
+            </head>"#;
+        assert!(links(html).is_empty());
+    }
+
+    #[test]
+    fn links_after_the_head_are_left_out() {
+        let html = r#"<head></head><body><link rel="stylesheet" href="/late.css"></body>"#;
+        assert!(links(html).is_empty());
+    }
+
+    #[test]
+    fn the_start_of_a_page_is_enough() {
+        // The first chunk of a streamed page may end before the head does
+        let html = r#"<head><link rel="stylesheet" href="/pkg/app.css"><link rel="modulepre"#;
+        assert_eq!(links(html), ["</pkg/app.css>; rel=preload; as=style"]);
+    }
+
+    #[test]
+    fn attributes_can_be_quoted_any_way() {
+        assert_eq!(
+            parse_attributes(r#" rel='preload' href=/pkg/app.wasm as = "fetch" crossorigin /"#),
+            [
+                ("rel".to_string(), "preload".to_string()),
+                ("href".to_string(), "/pkg/app.wasm".to_string()),
+                ("as".to_string(), "fetch".to_string()),
+                ("crossorigin".to_string(), String::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn attribute_names_are_case_insensitive() {
+        let html = r#"<head><LINK REL="stylesheet" HREF="/pkg/app.css"></head>"#;
+        // Only lowercase tags are looked for, that's what Leptos renders
+        assert!(links(html).is_empty());
+        let html = r#"<head><link REL="stylesheet" HREF="/pkg/app.css"></head>"#;
+        assert_eq!(links(html), ["</pkg/app.css>; rel=preload; as=style"]);
+    }
+}
@@ -0,0 +1,394 @@
+use crate::page_cache::IncompleteBody;
+use futures::future::{select, Either};
+use futures::stream::unfold;
+use futures::{Future, StreamExt};
+use leptos_integration_utils::{PinnedFuture, PinnedStream};
+use pavex::http::header::CONTENT_TYPE;
+use pavex::http::{HeaderValue, StatusCode};
+use pavex::response::body::raw::Full;
+use pavex::response::Response;
+use std::pin::pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Appended to an in-order page that had already started streaming when its deadline passed, so
+/// the truncated document can be told apart from a complete one.
+pub(crate) const TIMEOUT_MARKER: &str = "<!--leptos-pavex-timeout--><script>console.error(\
+    \"The server took too long to render this page, the rest of it is missing.\")</script>";
+
+/// What to do when a page isn't rendered before its deadline.
+#[derive(Clone, Debug)]
+pub enum OnRenderTimeout {
+    /// Send the page right away with the fallbacks of every `<Suspense/>` that hasn't resolved,
+    /// and stream in the rest as it resolves. Pages with this deadline are rendered out of order
+    /// from the start, with every fragment that resolves before the deadline put in place on the
+    /// server, so nothing gets rendered twice.
+    Stream,
+    /// Stop rendering and answer with a `504 Gateway Timeout` and this HTML page instead.
+    ErrorPage(Arc<str>),
+}
+
+/// How long `SsrMode::Async` and `SsrMode::InOrder` routes may take to render. The deadline
+/// covers the whole page. If it passes before anything was sent, the page is answered as
+/// `on_timeout` says. In-order routes start streaming with their first chunk, so if the deadline
+/// passes after that the rest of the page is cut off instead, since the status has been sent.
+#[derive(Clone, Debug)]
+pub struct RenderDeadline {
+    pub timeout: Duration,
+    pub on_timeout: OnRenderTimeout,
+}
+
+impl RenderDeadline {
+    pub fn new(timeout: Duration, on_timeout: OnRenderTimeout) -> Self {
+        Self {
+            timeout,
+            on_timeout,
+        }
+    }
+}
+
+/// When the page being rendered has to be done. Provided to the stream builders, so in-order
+/// routes can cut their stream off once the response has started.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct RenderExpiry(pub(crate) Instant);
+
+/// Run `render` until the deadline passes. Returns `None` if it didn't produce a response in time.
+pub(crate) async fn render_until<F>(expiry: RenderExpiry, render: F) -> Option<Response>
+where
+    F: Future<Output = Response>,
+{
+    render_before(sleep_until(expiry.0), render).await
+}
+
+async fn render_before<F>(deadline: impl Future<Output = ()>, render: F) -> Option<Response>
+where
+    F: Future<Output = Response>,
+{
+    match select(pin!(render), pin!(deadline)).await {
+        Either::Left((res, _)) => Some(res),
+        Either::Right(_) => None,
+    }
+}
+
+/// The response for a page that missed its deadline.
+pub(crate) fn timeout_page(deadline: &RenderDeadline) -> Response {
+    let page = match &deadline.on_timeout {
+        OnRenderTimeout::ErrorPage(page) => page.to_string(),
+        OnRenderTimeout::Stream => "<!DOCTYPE html><html><head><title>Gateway Timeout</title>\
+                                    </head><body><h1>Gateway Timeout</h1></body></html>"
+            .to_string(),
+    };
+    Response::new(StatusCode::GATEWAY_TIMEOUT)
+        .insert_header(
+            CONTENT_TYPE,
+            HeaderValue::from_static("text/html; charset=utf-8"),
+        )
+        .set_raw_body(Full::new(page.into()))
+}
+
+/// Pass the stream through until the deadline, then end it with [`TIMEOUT_MARKER`]. Until the
+/// first chunk is out the stream just waits, so the response can still be answered as the
+/// deadline says.
+pub(crate) fn cut_off_at(
+    stream: PinnedStream<String>,
+    expiry: RenderExpiry,
+) -> PinnedStream<String> {
+    let incomplete = IncompleteBody::current();
+    Box::pin(unfold(
+        Some((stream, None::<PinnedFuture<()>>)),
+        move |state| {
+            let incomplete = incomplete.clone();
+            async move {
+                let (mut stream, timer) = state?;
+                let Some(mut timer) = timer else {
+                    let chunk = stream.next().await?;
+                    let timer = Box::pin(sleep_until(expiry.0)) as PinnedFuture<()>;
+                    return Some((chunk, Some((stream, Some(timer)))));
+                };
+                let next = match select(stream.next(), timer.as_mut()).await {
+                    Either::Left((chunk, _)) => Some(chunk),
+                    Either::Right(_) => None,
+                };
+                match next {
+                    Some(Some(chunk)) => Some((chunk, Some((stream, Some(timer))))),
+                    Some(None) => None,
+                    None => {
+                        tracing::warn!("Render deadline passed, cutting the rest of the page off");
+                        if let Some(incomplete) = incomplete {
+                            incomplete.raise();
+                        }
+                        Some((TIMEOUT_MARKER.to_string(), None))
+                    }
+                }
+            }
+        },
+    ))
+}
+
+/// Hold an out-of-order stream back until the deadline, putting each `<Suspense/>` fragment that
+/// resolves in time in place of its fallback, the way the page would have been rendered in order.
+/// Once the stream is done or the deadline passes, the page is sent as it is, and the fragments
+/// that are still pending stream in after it to be swapped in on the client.
+///
+/// With `flush_resolved`, the start of the page is sent as soon as there's no fallback left in
+/// it, the way in-order streaming sends it.
+pub(crate) fn resolve_until(
+    stream: PinnedStream<String>,
+    expiry: RenderExpiry,
+    flush_resolved: bool,
+) -> PinnedStream<String> {
+    hold_until(stream, sleep_until(expiry.0), flush_resolved)
+}
+
+enum Held {
+    Holding {
+        stream: PinnedStream<String>,
+        deadline: PinnedFuture<()>,
+        page: String,
+    },
+    Streaming(PinnedStream<String>),
+    Done,
+}
+
+fn hold_until(
+    stream: PinnedStream<String>,
+    deadline: impl Future<Output = ()> + Send + 'static,
+    flush_resolved: bool,
+) -> PinnedStream<String> {
+    let held = Held::Holding {
+        stream,
+        deadline: Box::pin(deadline),
+        page: String::new(),
+    };
+    Box::pin(unfold(held, move |held| async move {
+        let (mut stream, mut deadline, mut page) = match held {
+            Held::Holding {
+                stream,
+                deadline,
+                page,
+            } => (stream, deadline, page),
+            Held::Streaming(mut stream) => {
+                let chunk = stream.next().await?;
+                return Some((chunk, Held::Streaming(stream)));
+            }
+            Held::Done => return None,
+        };
+        loop {
+            match select(stream.next(), deadline.as_mut()).await {
+                Either::Left((Some(chunk), _)) => {
+                    splice_fragments(&mut page, &chunk);
+                    let resolved = page.find(CHUNK_MARKER).unwrap_or(page.len());
+                    if flush_resolved && resolved > 0 {
+                        let pending = page.split_off(resolved);
+                        let held = Held::Holding {
+                            stream,
+                            deadline,
+                            page: pending,
+                        };
+                        return Some((page, held));
+                    }
+                }
+                Either::Left((None, _)) => {
+                    return (!page.is_empty()).then_some((page, Held::Done));
+                }
+                Either::Right(_) => {
+                    tracing::warn!(
+                        "Render deadline passed, streaming the rest of the page out of order"
+                    );
+                    if page.is_empty() {
+                        let chunk = stream.next().await?;
+                        return Some((chunk, Held::Streaming(stream)));
+                    }
+                    return Some((page, Held::Streaming(stream)));
+                }
+            }
+        }
+    }))
+}
+
+/// The start of the comments an out-of-order stream wraps the fallback of a pending `<Suspense/>`
+/// in, `<!--s-{id}o-->` before it and `<!--s-{id}c-->` after it.
+const CHUNK_MARKER: &str = "<!--s-";
+const TEMPLATE_START: &str = "<template id=\"";
+
+/// Add a chunk of an out-of-order stream to the page. A resolved fragment arrives as a `<template>`
+/// with the id of its `<Suspense/>`, followed by the script that swaps it in for the fallback. The
+/// fragment replaces the fallback here instead, without the script. Anything that can't be put in
+/// place is appended as it is, for the client to deal with.
+fn splice_fragments(page: &mut String, mut chunk: &str) {
+    while let Some(start) = chunk.find(TEMPLATE_START) {
+        page.push_str(&chunk[..start]);
+        chunk = &chunk[start..];
+        let Some((id, content, len)) = parse_fragment(chunk) else {
+            break;
+        };
+        let open = format!("{CHUNK_MARKER}{id}o-->");
+        let close = format!("{CHUNK_MARKER}{id}c-->");
+        let range = page.find(&open).and_then(|open_at| {
+            let close_at = open_at + page[open_at..].find(&close)?;
+            Some(open_at..close_at + close.len())
+        });
+        match range {
+            Some(range) => page.replace_range(range, content),
+            None => page.push_str(&chunk[..len]),
+        }
+        chunk = &chunk[len..];
+    }
+    page.push_str(chunk);
+}
+
+/// Split a `<template id="{id}f">{content}</template><script>...</script>` off the start of
+/// `chunk`. Returns the id, the content and the length of the whole fragment.
+fn parse_fragment(chunk: &str) -> Option<(&str, &str, usize)> {
+    let rest = chunk.strip_prefix(TEMPLATE_START)?;
+    let id_end = rest.find("f\">")?;
+    let id = &rest[..id_end];
+    let content_start = TEMPLATE_START.len() + id_end + "f\">".len();
+
+    // The fragment can have templates of its own, so find the one that closes it
+    let mut depth = 0;
+    let mut at = content_start;
+    let content_end = loop {
+        let next_open = chunk[at..].find("<template").map(|i| at + i);
+        let next_close = at + chunk[at..].find("</template>")?;
+        match next_open {
+            Some(open) if open < next_close => {
+                depth += 1;
+                at = open + "<template".len();
+            }
+            _ if depth > 0 => {
+                depth -= 1;
+                at = next_close + "</template>".len();
+            }
+            _ => break next_close,
+        }
+    };
+    let script_start = content_end + "</template>".len();
+    if !chunk[script_start..].starts_with("<script") {
+        return None;
+    }
+    let script_end = script_start + chunk[script_start..].find("</script>")? + "</script>".len();
+    Some((id, &chunk[content_start..content_end], script_end))
+}
+
+#[cfg(feature = "ssr")]
+fn sleep_until(instant: Instant) -> impl Future<Output = ()> {
+    tokio::time::sleep_until(instant.into())
+}
+
+// There's no timer we can rely on outside of tokio, so deadlines never pass
+#[cfg(not(feature = "ssr"))]
+fn sleep_until(_instant: Instant) -> impl Future<Output = ()> {
+    futures::future::pending()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::future::{pending, poll_fn, ready};
+    use futures::stream::{iter, once};
+    use std::task::Poll;
+
+    const SHELL: &str = "<html><head></head><body><p>Start</p>\
+                         <!--s-1-o--><p>Loading</p><!--s-1-c--><p>End</p></body></html>";
+    const FRAGMENT: &str = "<template id=\"1-f\"><p>Loaded</p></template>\
+                            <script>(function() { let id = \"1-\"; })()</script>";
+
+    /// Resolves to `value`, but only after having been polled once, like a resource that isn't
+    /// ready yet
+    fn after_a_poll<T: Unpin>(value: T) -> impl Future<Output = T> {
+        let mut value = Some(value);
+        let mut polled = false;
+        poll_fn(move |cx| {
+            if !polled {
+                polled = true;
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            Poll::Ready(value.take().unwrap())
+        })
+    }
+
+    fn collect(stream: PinnedStream<String>) -> Vec<String> {
+        futures::executor::block_on(stream.collect())
+    }
+
+    fn chunks(chunks: &[&str]) -> PinnedStream<String> {
+        Box::pin(iter(
+            chunks
+                .iter()
+                .map(|chunk| chunk.to_string())
+                .collect::<Vec<_>>(),
+        ))
+    }
+
+    #[test]
+    fn fragments_resolved_before_the_deadline_are_put_in_place() {
+        let stream = hold_until(chunks(&[SHELL, FRAGMENT]), pending(), false);
+        assert_eq!(
+            collect(stream),
+            ["<html><head></head><body><p>Start</p><p>Loaded</p><p>End</p></body></html>"]
+        );
+    }
+
+    #[test]
+    fn in_order_pages_send_their_start_once_it_is_resolved() {
+        let stream = hold_until(chunks(&[SHELL, FRAGMENT]), pending(), true);
+        assert_eq!(
+            collect(stream),
+            [
+                "<html><head></head><body><p>Start</p>",
+                "<p>Loaded</p><p>End</p></body></html>"
+            ]
+        );
+    }
+
+    #[test]
+    fn pages_stream_their_fallbacks_once_the_deadline_passes() {
+        let late_fragment = once(after_a_poll(FRAGMENT.to_string()));
+        let stream = Box::pin(chunks(&[SHELL]).chain(late_fragment));
+        // Nothing is rendered again, the fragment that was still pending streams in after the
+        // page so the client can swap it in
+        assert_eq!(
+            collect(hold_until(stream, ready(()), false)),
+            [SHELL, FRAGMENT]
+        );
+    }
+
+    #[test]
+    fn nested_fragments_are_put_in_place() {
+        let outer = "<template id=\"1-f\"><template><p>Inert</p></template>\
+                     <!--s-1-0-o--><p>Loading more</p><!--s-1-0-c--></template><script></script>";
+        let inner = "<template id=\"1-0-f\"><p>Deep</p></template><script></script>";
+        let stream = hold_until(chunks(&[SHELL, outer, inner]), pending(), false);
+        assert_eq!(
+            collect(stream),
+            [
+                "<html><head></head><body><p>Start</p><template><p>Inert</p></template>\
+              <p>Deep</p><p>End</p></body></html>"
+            ]
+        );
+    }
+
+    #[test]
+    fn fragments_without_a_fallback_are_left_for_the_client() {
+        let stream = hold_until(chunks(&["<p>No fallbacks</p>", FRAGMENT]), pending(), false);
+        assert_eq!(collect(stream), [format!("<p>No fallbacks</p>{FRAGMENT}")]);
+    }
+
+    #[test]
+    fn pages_that_miss_the_deadline_get_the_error_page() {
+        let missed = render_before(ready(()), after_a_poll(Response::ok()));
+        assert!(futures::executor::block_on(missed).is_none());
+        let made_it = render_before(pending(), after_a_poll(Response::ok()));
+        assert!(futures::executor::block_on(made_it).is_some());
+
+        let deadline = RenderDeadline::new(
+            Duration::from_secs(1),
+            OnRenderTimeout::ErrorPage("<h1>Too slow</h1>".into()),
+        );
+        let res = timeout_page(&deadline);
+        assert_eq!(res.status(), StatusCode::GATEWAY_TIMEOUT);
+        assert_eq!(res.headers()[CONTENT_TYPE], "text/html; charset=utf-8");
+    }
+}
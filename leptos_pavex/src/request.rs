@@ -1,7 +1,13 @@
+use crate::response_options::ResponseOptions;
 use bytes::Bytes;
 use futures::{Stream, StreamExt};
-use http_body_util::BodyExt;
+use http_body::Body;
+use http_body_util::{BodyExt, LengthLimitError, Limited};
+use leptos::prelude::use_context;
 use leptos::server_fn::{error::ServerFnError, request::Req};
+use pavex::http::header::{ACCEPT, REFERER};
+use pavex::http::StatusCode;
+use pavex::request::body::errors::ExtractBufferedBodyError;
 use pavex::request::body::{BodySizeLimit, BufferedBody, RawIncomingBody};
 use pavex::request::RequestHead;
 use std::borrow::Cow;
@@ -11,10 +17,39 @@ use std::borrow::Cow;
 pub struct PavexRequest {
     pub head: RequestHead,
     pub body: RawIncomingBody,
+    /// The most the body may hold. Bodies over it are rejected instead of being buffered.
+    pub body_limit: BodySizeLimit,
 }
 impl PavexRequest {
+    /// A request without a body limit. `handle_server_fns` sets the one of the server function,
+    /// see [`PavexRouteIndex::body_limit_for`](crate::PavexRouteIndex::body_limit_for).
     pub fn new_from_req(head: RequestHead, body: RawIncomingBody) -> Self {
-        Self { head, body }
+        Self {
+            head,
+            body,
+            body_limit: BodySizeLimit::Disabled,
+        }
+    }
+
+    pub fn with_body_limit(mut self, body_limit: BodySizeLimit) -> Self {
+        self.body_limit = body_limit;
+        self
+    }
+
+    async fn buffer_body<CustErr>(self) -> Result<Bytes, ServerFnError<CustErr>> {
+        let buf = BufferedBody::extract(&self.head, self.body, self.body_limit)
+            .await
+            .map_err(|e| {
+                if let ExtractBufferedBodyError::SizeLimitExceeded(_) = e {
+                    // The error is serialized by `error_response`, this makes it go out as a 413
+                    if let Some(res_options) = use_context::<ResponseOptions>() {
+                        res_options.set_status(StatusCode::PAYLOAD_TOO_LARGE);
+                    }
+                    return ServerFnError::Request(e.to_string());
+                }
+                ServerFnError::Deserialization(e.to_string())
+            })?;
+        Ok(buf.bytes)
     }
 }
 
@@ -34,32 +69,33 @@ where
             .map(|h| String::from_utf8_lossy(h.as_bytes()))
     }
 
+    /// server_fn sends plain HTML form posts back to their Referer with
+    /// [`Res::redirect`](leptos::server_fn::response::Res::redirect). A form post without one has
+    /// nowhere to go back to, so it's answered like a call from the server fn client.
     fn accepts(&self) -> Option<Cow<'_, str>> {
         let headers = &self.head.headers;
+        if !headers.contains_key(REFERER) {
+            return None;
+        }
         headers
-            .get("Acceot")
+            .get(ACCEPT)
             .map(|h| String::from_utf8_lossy(h.as_bytes()))
     }
 
     fn referer(&self) -> Option<Cow<'_, str>> {
         let headers = &self.head.headers;
         headers
-            .get("Referer")
+            .get(REFERER)
             .map(|h| String::from_utf8_lossy(h.as_bytes()))
     }
 
     async fn try_into_bytes(self) -> Result<Bytes, ServerFnError<CustErr>> {
-        let buf = BufferedBody::extract(&self.head, self.body, BodySizeLimit::Disabled)
-            .await
-            .map_err(|e| ServerFnError::Deserialization(e.to_string()))?;
-        Ok(buf.bytes)
+        self.buffer_body().await
     }
 
     async fn try_into_string(self) -> Result<String, ServerFnError<CustErr>> {
-        let buf = BufferedBody::extract(&self.head, self.body, BodySizeLimit::Disabled)
-            .await
-            .map_err(|e| ServerFnError::Deserialization(e.to_string()))?;
-        String::from_utf8(Vec::from(buf.bytes))
+        let bytes = self.buffer_body().await?;
+        String::from_utf8(Vec::from(bytes))
             .map_err(|e| ServerFnError::Deserialization(e.to_string()))
     }
 
@@ -69,9 +105,99 @@ where
         impl Stream<Item = Result<Bytes, ServerFnError>> + Send + 'static,
         ServerFnError<CustErr>,
     > {
-        Ok(self
-            .body
-            .into_data_stream()
-            .map(|chunk| chunk.map_err(|e| ServerFnError::Deserialization(e.to_string()))))
+        Ok(limited_data_stream(self.body, self.body_limit))
+    }
+}
+
+/// The data in `body`, ending in an error once there's more of it than `body_limit` allows. The
+/// response is only sent once the server function is done reading, so going over the limit still
+/// makes it a 413.
+fn limited_data_stream<B>(
+    body: B,
+    body_limit: BodySizeLimit,
+) -> impl Stream<Item = Result<Bytes, ServerFnError>> + Send + 'static
+where
+    B: Body<Data = Bytes> + Send + 'static,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    let max_size = match body_limit {
+        BodySizeLimit::Enabled { max_size } => {
+            usize::try_from(max_size.as_u64()).unwrap_or(usize::MAX)
+        }
+        BodySizeLimit::Disabled => usize::MAX,
+    };
+    let res_options = use_context::<ResponseOptions>();
+    Limited::new(body, max_size)
+        .into_data_stream()
+        .map(move |chunk| {
+            chunk.map_err(|e| {
+                if e.is::<LengthLimitError>() {
+                    if let Some(res_options) = &res_options {
+                        res_options.set_status(StatusCode::PAYLOAD_TOO_LARGE);
+                    }
+                    return ServerFnError::Request(e.to_string());
+                }
+                ServerFnError::Deserialization(e.to_string())
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+    use http_body::Frame;
+    use http_body_util::{Full, StreamBody};
+    use leptos::prelude::{provide_context, Owner};
+    use std::convert::Infallible;
+
+    fn chunked_body(
+        chunks: &[&'static str],
+    ) -> StreamBody<impl Stream<Item = Result<Frame<Bytes>, Infallible>>> {
+        let frames = chunks
+            .iter()
+            .map(|chunk| Ok(Frame::data(Bytes::from_static(chunk.as_bytes()))))
+            .collect::<Vec<_>>();
+        StreamBody::new(futures::stream::iter(frames))
+    }
+
+    #[test]
+    fn bodies_within_the_limit_are_streamed_whole() {
+        let limit = BodySizeLimit::Enabled {
+            max_size: 8usize.into(),
+        };
+        let chunks = block_on(
+            limited_data_stream(chunked_body(&["abcd", "efgh"]), limit).collect::<Vec<_>>(),
+        );
+        let data = chunks.into_iter().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(data.concat(), b"abcdefgh");
+    }
+
+    #[test]
+    fn streamed_bodies_over_the_limit_are_a_413() {
+        let owner = Owner::new();
+        let res_options = ResponseOptions::default();
+        let limit = BodySizeLimit::Enabled {
+            max_size: 6usize.into(),
+        };
+        let stream = owner.with(|| {
+            provide_context(res_options.clone());
+            limited_data_stream(chunked_body(&["abcd", "efgh"]), limit)
+        });
+        let chunks = block_on(stream.collect::<Vec<_>>());
+        assert!(matches!(
+            chunks.last(),
+            Some(Err(ServerFnError::Request(_)))
+        ));
+        assert_eq!(res_options.status(), Some(StatusCode::PAYLOAD_TOO_LARGE));
+    }
+
+    #[test]
+    fn unlimited_bodies_are_streamed_whole() {
+        let body = Full::new(Bytes::from(vec![0; 4096]));
+        let chunks =
+            block_on(limited_data_stream(body, BodySizeLimit::Disabled).collect::<Vec<_>>());
+        let data = chunks.into_iter().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(data.concat().len(), 4096);
     }
 }
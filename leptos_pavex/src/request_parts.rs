@@ -5,7 +5,7 @@ use pavex::request::RequestHead;
 // Because IncomingRequest is not Clone, we provide this struct with the
 // easily cloneable parts.
 // TODO: Evaluate whether Body can go here(perhaps as Bytes) without breaking Streaming
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct RequestParts {
     method: Method,
     scheme: Option<Scheme>,
@@ -13,11 +13,7 @@ pub struct RequestParts {
 }
 impl RequestParts {
     pub fn new() -> Self {
-        Self {
-            method: Method::default(),
-            headers: HeaderMap::default(),
-            scheme: None,
-        }
+        Self::default()
     }
 
     pub fn new_from_req(req: &RequestHead) -> Self {
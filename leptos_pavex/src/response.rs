@@ -1,26 +1,32 @@
+use crate::error_status::{status_code_for, ErrorStatus};
 use crate::extend_response::ExtendResponse;
+use crate::page_cache::IncompleteBody;
+use crate::panic_page::{log_panic, panic_page, PANIC_MARKER};
 use crate::pavex_helpers::AppFunction;
+use crate::preload::preload_links;
 use crate::response_options::ResponseOptions;
-use crate::stream::{LeptosPavexStream, PavexStream};
+use crate::stream::{BufferedLeptosPavexStream, ChunkBuffering, PavexStream};
+use crate::PavexRouteIndex;
 use bytes::Bytes;
-use futures::{Stream, StreamExt};
+use futures::{FutureExt, Stream, StreamExt};
 use futures_util::stream::once;
 use leptos::nonce::use_nonce;
+use leptos::prelude::use_context;
 use leptos::server_fn::error::{
     ServerFnError, ServerFnErrorErr, ServerFnErrorSerde, SERVER_FN_ERROR_HEADER,
 };
 use leptos::server_fn::response::Res;
 use leptos_integration_utils::{BoxedFnOnce, PinnedFuture, PinnedStream};
 use leptos_meta::ServerMetaContext;
-use pavex::http::header::CONTENT_TYPE;
+use pavex::http::header::{CONTENT_TYPE, LINK, LOCATION};
 use pavex::http::HeaderValue;
 use pavex::http::{HeaderMap, HeaderName, StatusCode};
 use pavex::response::Response;
 use reactive_graph::owner::{Owner, Sandboxed};
+use std::panic::AssertUnwindSafe;
 use std::pin::Pin;
 use std::{
     fmt::{Debug, Display},
-    future::Future,
     str::FromStr,
 };
 
@@ -28,14 +34,24 @@ use std::{
 /// the generic error. So we have to wrap it to make it happy
 pub struct PavexResponse(pub Response);
 
+impl PavexResponse {
+    /// A rendered page, batched into frames with the app's settings.
+    fn from_page_stream(
+        stream: impl Stream<Item = String> + Send + 'static,
+        buffering: ChunkBuffering,
+    ) -> Self {
+        let stream = stream.map(|chunk| Ok(chunk) as Result<String, std::io::Error>);
+
+        let lp_stream = BufferedLeptosPavexStream::new(stream).with_buffering(buffering);
+        PavexResponse(Response::ok().set_raw_body(lp_stream))
+    }
+}
+
 impl ExtendResponse for PavexResponse {
     type ResponseOptions = ResponseOptions;
 
     fn from_stream(stream: impl Stream<Item = String> + Send + 'static) -> Self {
-        let stream = stream.map(|chunk| Ok(chunk) as Result<String, std::io::Error>);
-
-        let lp_stream = LeptosPavexStream { inner: stream };
-        PavexResponse(Response::ok().set_raw_body(lp_stream))
+        Self::from_page_stream(stream, ChunkBuffering::default())
     }
 
     fn extend_response(&mut self, res_options: &Self::ResponseOptions) {
@@ -57,7 +73,7 @@ impl ExtendResponse for PavexResponse {
         }
     }
 
-    fn from_app(
+    async fn from_app(
         app_fn: AppFunction,
         meta_context: ServerMetaContext,
         additional_context: impl FnOnce() + Send + 'static,
@@ -66,16 +82,62 @@ impl ExtendResponse for PavexResponse {
             AppFunction,
             BoxedFnOnce<PinnedStream<String>>,
         ) -> PinnedFuture<PinnedStream<String>>,
-    ) -> impl Future<Output = Self> + Send {
-        async move {
+    ) -> Self {
+        let Some(owner) = Owner::current() else {
+            panic!("Failed to get Owner for components!");
+        };
+        // Provided by `render_route`, along with the app's other handles
+        let routes = owner.with(use_context::<PavexRouteIndex>);
+        let buffering = routes
+            .as_ref()
+            .map(PavexRouteIndex::chunk_buffering)
+            .unwrap_or_default();
+        let started = AssertUnwindSafe(async move {
             let (owner, stream) =
                 build_response(app_fn, meta_context, additional_context, stream_builder);
             let mut stream = stream.await;
 
             // wait for the first chunk of the stream, then set the status and headers
             let first_chunk = stream.next().await.unwrap_or_default();
+            (owner, stream, first_chunk)
+        })
+        .catch_unwind()
+        .await;
 
-            let mut res = Self::from_stream(Sandboxed::new(
+        // If the app panicked before we sent anything, we can still answer with an error page
+        let (owner, stream, first_chunk) = match started {
+            Ok(started) => started,
+            Err(payload) => {
+                log_panic(&owner, &*payload);
+                let page = panic_page(routes.as_ref());
+                let mut res = Self::from_page_stream(once(async move { page }), buffering);
+                *res.0.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+                res.set_default_content_type("text/html; charset=utf-8");
+                return res;
+            }
+        };
+
+        // The shell is in the first chunk, so this is where we learn which assets the page needs
+        let preload_links = preload_links(&first_chunk);
+
+        // Past this point the status has been sent, so the best we can do is mark the end of
+        // the document, and keep the page cache from storing it
+        let incomplete = IncompleteBody::current();
+        let stream = AssertUnwindSafe(stream).catch_unwind().map({
+            let owner = owner.clone();
+            move |chunk| {
+                chunk.unwrap_or_else(|payload| {
+                    log_panic(&owner, &*payload);
+                    if let Some(incomplete) = &incomplete {
+                        incomplete.raise();
+                    }
+                    PANIC_MARKER.to_string()
+                })
+            }
+        });
+
+        let mut res = Self::from_page_stream(
+            Sandboxed::new(
                 once(async move { first_chunk })
                     .chain(stream)
                     // drop the owner, cleaning up the reactive runtime,
@@ -84,16 +146,20 @@ impl ExtendResponse for PavexResponse {
                         drop(owner);
                         Default::default()
                     })),
-            ));
+            ),
+            buffering,
+        );
 
-            res.extend_response(&res_options);
+        res.extend_response(&res_options);
+        for link in preload_links {
+            res.0.headers_mut().append(LINK, link);
+        }
 
-            // Set the Content Type headers on all responses. This makes Firefox show the page source
-            // without complaining
-            res.set_default_content_type("text/html; charset=utf-8");
+        // Set the Content Type headers on all responses. This makes Firefox show the page source
+        // without complaining
+        res.set_default_content_type("text/html; charset=utf-8");
 
-            res
-        }
+        res
     }
 }
 
@@ -152,7 +218,7 @@ pub fn build_response(
 }
 impl<CustErr> Res<CustErr> for PavexResponse
 where
-    CustErr: Send + Sync + Debug + FromStr + Display + 'static,
+    CustErr: ErrorStatus + Send + Sync + Debug + FromStr + Display + 'static,
 {
     fn try_from_string(content_type: &str, data: String) -> Result<Self, ServerFnError<CustErr>> {
         let mut headers = HeaderMap::new();
@@ -202,7 +268,7 @@ where
     }
 
     fn error_response(path: &str, err: &ServerFnError<CustErr>) -> Self {
-        let res = Response::new(StatusCode::INTERNAL_SERVER_ERROR)
+        let res = Response::new(status_code_for(err))
             .insert_header(
                 HeaderName::from_static(SERVER_FN_ERROR_HEADER),
                 HeaderValue::from_str(path).unwrap(),
@@ -211,11 +277,17 @@ where
         PavexResponse(res)
     }
 
-    fn redirect(&mut self, _path: &str) {
-        //TODO: Enabling these seems to override location header
-        // not sure what's causing that
-        //let res_options = expect_context::<ResponseOptions>();
-        //res_options.insert_header("Location", path);
-        //res_options.set_status(302);
+    /// Called by the server function itself to send a plain HTML form post back where it came
+    /// from. A `Location` set with [`crate::redirect`] goes into the `ResponseOptions`, which are
+    /// applied after this, so it still wins. A failed server function keeps the status from
+    /// [`status_code_for`] and isn't redirected.
+    fn redirect(&mut self, path: &str) {
+        if self.0.status().is_client_error() || self.0.status().is_server_error() {
+            return;
+        }
+        if let Ok(path) = HeaderValue::from_str(path) {
+            self.0.headers_mut().insert(LOCATION, path);
+            *self.0.status_mut() = StatusCode::SEE_OTHER;
+        }
     }
 }
@@ -0,0 +1,361 @@
+use crate::static_routes::{self, StaticParams};
+use crate::{init_executor, PavexRouteList, PavexRouteListing};
+use leptos_router::{SsrMode, StaticDataMap, StaticMode};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Write the route list to a JSON file. Do this in your blueprint generator, after registering the
+/// routes, so the server can load the same list with [`read_route_list`] or
+/// [`route_list_from_json`] instead of walking the app tree again.
+///
+/// The paths of statically rendered routes are written along with them. Use
+/// [`write_route_list_with_static_params`] if any of them have params.
+pub fn write_route_list(path: impl AsRef<Path>, routes: &PavexRouteList) -> io::Result<()> {
+    write_route_list_with_static_params(path, routes, &StaticParams::new())
+}
+
+/// Like [`write_route_list`], but statically rendered routes with params are written with a path
+/// for every combination of the values `static_params` has for them, so
+/// [`build_static_routes`](static_routes::build_static_routes) doesn't need the params again.
+pub fn write_route_list_with_static_params(
+    path: impl AsRef<Path>,
+    routes: &PavexRouteList,
+    static_params: &StaticParams,
+) -> io::Result<()> {
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let routes = routes
+        .iter()
+        .cloned()
+        .map(|mut listing| {
+            if listing.static_mode() == Some(StaticMode::Upfront) {
+                listing.static_paths = Some(static_routes::static_paths(&listing, static_params));
+            }
+            listing
+        })
+        .collect::<PavexRouteList>();
+    let json = serde_json::to_string_pretty(&routes)?;
+    fs::write(path, json + "\n")
+}
+
+/// Read a route list written by [`write_route_list`].
+pub fn read_route_list(path: impl AsRef<Path>) -> io::Result<PavexRouteList> {
+    route_list_from_json(&fs::read_to_string(path)?)
+}
+
+/// Parse a route list written by [`write_route_list`], e.g. one embedded in the server binary with
+/// `include_str!`, so the server doesn't need the file at runtime.
+///
+/// This stands in for generating the route list in the server, so it sets up the executor Leptos
+/// spawns resources on the same way.
+pub fn route_list_from_json(json: &str) -> io::Result<PavexRouteList> {
+    init_executor();
+    Ok(serde_json::from_str(json)?)
+}
+
+/// Compare the route list written to `path` with the routes the app has now. Returns an error
+/// of kind [`io::ErrorKind::InvalidData`] wrapping a [`RouteListDrift`] when they differ, which
+/// means the server SDK was generated for different routes and has to be generated again.
+pub fn check_route_list(path: impl AsRef<Path>, routes: &PavexRouteList) -> io::Result<()> {
+    let written = read_route_list(path)?;
+    let by_path = |routes: &PavexRouteList| {
+        routes
+            .iter()
+            .map(|listing| {
+                // The static paths come from the app's data rather than its routes, so they
+                // aren't compared
+                let serialized = SerializedRouteListing {
+                    static_paths: None,
+                    ..SerializedRouteListing::from(listing.clone())
+                };
+                (listing.path().to_string(), serialized)
+            })
+            .collect::<BTreeMap<_, _>>()
+    };
+    let written = by_path(&written);
+    let current = by_path(routes);
+
+    let drift = RouteListDrift {
+        added: current
+            .keys()
+            .filter(|path| !written.contains_key(*path))
+            .cloned()
+            .collect(),
+        removed: written
+            .keys()
+            .filter(|path| !current.contains_key(*path))
+            .cloned()
+            .collect(),
+        changed: current
+            .iter()
+            .filter(|(path, listing)| written.get(*path).is_some_and(|w| w != *listing))
+            .map(|(path, _)| path.clone())
+            .collect(),
+    };
+    if drift.added.is_empty() && drift.removed.is_empty() && drift.changed.is_empty() {
+        Ok(())
+    } else {
+        Err(io::Error::new(io::ErrorKind::InvalidData, drift))
+    }
+}
+
+/// How the routes of the app differ from a route list written earlier.
+#[derive(Clone, Debug, Default)]
+pub struct RouteListDrift {
+    /// Routes the app has that aren't in the written list.
+    pub added: Vec<String>,
+    /// Routes in the written list that the app doesn't have anymore.
+    pub removed: Vec<String>,
+    /// Routes whose rendering mode, methods or static mode changed.
+    pub changed: Vec<String>,
+}
+
+impl fmt::Display for RouteListDrift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "The Leptos routes don't match the generated route list")?;
+        for (label, paths) in [
+            ("added", &self.added),
+            ("removed", &self.removed),
+            ("changed", &self.changed),
+        ] {
+            if !paths.is_empty() {
+                write!(f, "; {label}: {}", paths.join(", "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Error for RouteListDrift {}
+
+/// The serialized form of a [`PavexRouteListing`]. The Leptos router types don't implement serde's
+/// traits, so they're mirrored here.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SerializedRouteListing {
+    path: String,
+    mode: SerializedSsrMode,
+    methods: Vec<SerializedMethod>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    static_mode: Option<SerializedStaticMode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    static_paths: Option<Vec<String>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum SerializedSsrMode {
+    OutOfOrder,
+    PartiallyBlocked,
+    InOrder,
+    Async,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+enum SerializedMethod {
+    Get,
+    Post,
+    Put,
+    Delete,
+    Patch,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum SerializedStaticMode {
+    Upfront,
+    Incremental,
+}
+
+impl From<PavexRouteListing> for SerializedRouteListing {
+    fn from(listing: PavexRouteListing) -> Self {
+        Self {
+            path: listing.path,
+            mode: match listing.mode {
+                SsrMode::OutOfOrder => SerializedSsrMode::OutOfOrder,
+                SsrMode::PartiallyBlocked => SerializedSsrMode::PartiallyBlocked,
+                SsrMode::InOrder => SerializedSsrMode::InOrder,
+                SsrMode::Async => SerializedSsrMode::Async,
+            },
+            methods: listing
+                .methods
+                .into_iter()
+                .map(|method| match method {
+                    leptos_router::Method::Get => SerializedMethod::Get,
+                    leptos_router::Method::Post => SerializedMethod::Post,
+                    leptos_router::Method::Put => SerializedMethod::Put,
+                    leptos_router::Method::Delete => SerializedMethod::Delete,
+                    leptos_router::Method::Patch => SerializedMethod::Patch,
+                })
+                .collect(),
+            static_mode: listing.static_mode.map(|(mode, _)| match mode {
+                StaticMode::Upfront => SerializedStaticMode::Upfront,
+                StaticMode::Incremental => SerializedStaticMode::Incremental,
+            }),
+            static_paths: listing.static_paths,
+        }
+    }
+}
+
+impl From<SerializedRouteListing> for PavexRouteListing {
+    fn from(listing: SerializedRouteListing) -> Self {
+        Self {
+            path: listing.path,
+            mode: match listing.mode {
+                SerializedSsrMode::OutOfOrder => SsrMode::OutOfOrder,
+                SerializedSsrMode::PartiallyBlocked => SsrMode::PartiallyBlocked,
+                SerializedSsrMode::InOrder => SsrMode::InOrder,
+                SerializedSsrMode::Async => SsrMode::Async,
+            },
+            methods: listing
+                .methods
+                .into_iter()
+                .map(|method| match method {
+                    SerializedMethod::Get => leptos_router::Method::Get,
+                    SerializedMethod::Post => leptos_router::Method::Post,
+                    SerializedMethod::Put => leptos_router::Method::Put,
+                    SerializedMethod::Delete => leptos_router::Method::Delete,
+                    SerializedMethod::Patch => leptos_router::Method::Patch,
+                })
+                .collect(),
+            // The static data isn't serialized, only whether the route is static and its paths
+            static_mode: listing.static_mode.map(|mode| {
+                let mode = match mode {
+                    SerializedStaticMode::Upfront => StaticMode::Upfront,
+                    SerializedStaticMode::Incremental => StaticMode::Incremental,
+                };
+                (mode, StaticDataMap::new())
+            }),
+            static_paths: listing.static_paths,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::static_routes::StaticParamsMap;
+    use leptos_router::Method;
+    use std::path::PathBuf;
+
+    /// A route list file of its own for a test, removed when it's dropped
+    struct TempRouteList(PathBuf);
+
+    impl TempRouteList {
+        fn new(name: &str) -> Self {
+            Self(std::env::temp_dir().join(format!(
+                "leptos_pavex_{name}_{}/routes.json",
+                std::process::id()
+            )))
+        }
+    }
+
+    impl Drop for TempRouteList {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(self.0.parent().unwrap());
+        }
+    }
+
+    fn routes() -> PavexRouteList {
+        vec![
+            PavexRouteListing::new("/".to_string(), SsrMode::OutOfOrder, [Method::Get], None),
+            PavexRouteListing::new(
+                "/post/:id".to_string(),
+                SsrMode::Async,
+                [Method::Get, Method::Post],
+                Some((StaticMode::Upfront, StaticDataMap::new())),
+            ),
+            PavexRouteListing::new(
+                "/feed".to_string(),
+                SsrMode::InOrder,
+                [Method::Get],
+                Some((StaticMode::Incremental, StaticDataMap::new())),
+            ),
+        ]
+    }
+
+    fn serialized(routes: &PavexRouteList) -> Vec<SerializedRouteListing> {
+        routes.iter().cloned().map(Into::into).collect()
+    }
+
+    #[test]
+    fn route_lists_are_read_back_the_way_they_were_written() {
+        let file = TempRouteList::new("round_trip");
+        write_route_list(&file.0, &routes()).unwrap();
+        let read = read_route_list(&file.0).unwrap();
+        let mut expected = routes();
+        // `write_route_list` has nothing to fill in `:id` with
+        expected[1].static_paths = Some(Vec::new());
+        assert_eq!(serialized(&read), serialized(&expected));
+        assert_eq!(read[1].static_mode(), Some(StaticMode::Upfront));
+        assert_eq!(read[2].static_mode(), Some(StaticMode::Incremental));
+    }
+
+    #[test]
+    fn static_paths_are_written_with_the_params() {
+        let file = TempRouteList::new("static_paths");
+        let static_params = StaticParams::from([(
+            "/post/:id".to_string(),
+            StaticParamsMap::from([("id".to_string(), vec!["1".to_string(), "2".to_string()])]),
+        )]);
+        write_route_list_with_static_params(&file.0, &routes(), &static_params).unwrap();
+        let read = read_route_list(&file.0).unwrap();
+        assert_eq!(
+            read[1].static_paths,
+            Some(vec!["/post/1".to_string(), "/post/2".to_string()])
+        );
+        // Only routes rendered up front get their paths written
+        assert_eq!(read[2].static_paths, None);
+    }
+
+    #[test]
+    fn unchanged_routes_pass_the_check() {
+        let file = TempRouteList::new("unchanged");
+        let static_params = StaticParams::from([(
+            "/post/:id".to_string(),
+            StaticParamsMap::from([("id".to_string(), vec!["1".to_string()])]),
+        )]);
+        write_route_list_with_static_params(&file.0, &routes(), &static_params).unwrap();
+        check_route_list(&file.0, &routes()).unwrap();
+    }
+
+    #[test]
+    fn changed_routes_fail_the_check_with_what_changed() {
+        let file = TempRouteList::new("drift");
+        write_route_list(&file.0, &routes()).unwrap();
+
+        let mut current = routes();
+        current.remove(0);
+        current[0].mode = SsrMode::OutOfOrder;
+        current.push(PavexRouteListing::new(
+            "/about".to_string(),
+            SsrMode::OutOfOrder,
+            [Method::Get],
+            None,
+        ));
+
+        let err = check_route_list(&file.0, &current).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        let drift = err
+            .get_ref()
+            .and_then(|e| e.downcast_ref::<RouteListDrift>())
+            .unwrap();
+        assert_eq!(drift.added, ["/about"]);
+        assert_eq!(drift.removed, ["/"]);
+        assert_eq!(drift.changed, ["/post/:id"]);
+    }
+
+    #[test]
+    fn a_missing_route_list_fails_the_check() {
+        let file = TempRouteList::new("missing");
+        let err = check_route_list(&file.0, &routes()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+}
@@ -0,0 +1,329 @@
+use http::Method;
+use std::error::Error;
+use std::fmt;
+
+/// Where a route registered on the Blueprint comes from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RouteOrigin {
+    /// A page route generated from the Leptos router.
+    Leptos,
+    /// A server function.
+    ServerFn,
+    /// A route registered on the Blueprint by hand.
+    Pavex,
+}
+
+impl fmt::Display for RouteOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RouteOrigin::Leptos => "Leptos route",
+            RouteOrigin::ServerFn => "server function",
+            RouteOrigin::Pavex => "Pavex route",
+        })
+    }
+}
+
+/// A route as it's registered on the Blueprint.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegisteredRoute {
+    pub method: Method,
+    pub path: String,
+    pub origin: RouteOrigin,
+}
+
+impl RegisteredRoute {
+    pub fn new(method: Method, path: impl Into<String>, origin: RouteOrigin) -> Self {
+        Self {
+            method,
+            path: path.into(),
+            origin,
+        }
+    }
+}
+
+impl fmt::Display for RegisteredRoute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} ({})", self.method, self.path, self.origin)
+    }
+}
+
+/// Two routes that can't both be registered the way they are.
+#[derive(Clone, Debug)]
+pub enum RouteConflict {
+    /// Both routes have the same method and path.
+    Duplicate(RegisteredRoute, RegisteredRoute),
+    /// The routes have a param at the same position with different names, which Pavex's router
+    /// rejects.
+    ParamNameMismatch {
+        first: RegisteredRoute,
+        second: RegisteredRoute,
+        first_param: String,
+        second_param: String,
+    },
+    /// The routes only differ by the names of their params, so every request one of them matches
+    /// goes to the other and it can never be reached.
+    Unreachable {
+        route: RegisteredRoute,
+        shadowed_by: RegisteredRoute,
+    },
+}
+
+impl fmt::Display for RouteConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RouteConflict::Duplicate(first, second) => {
+                write!(f, "{first} and {second} are registered on the same path")
+            }
+            RouteConflict::ParamNameMismatch {
+                first,
+                second,
+                first_param,
+                second_param,
+            } => write!(
+                f,
+                "{first} and {second} name the same path segment differently \
+                 (`{first_param}` and `{second_param}`), rename one of them"
+            ),
+            RouteConflict::Unreachable { route, shadowed_by } => write!(
+                f,
+                "{route} can never be reached, every request it matches goes to {shadowed_by}"
+            ),
+        }
+    }
+}
+
+/// Every conflict found between the routes of an app.
+#[derive(Clone, Debug)]
+pub struct RouteConflicts(pub Vec<RouteConflict>);
+
+impl fmt::Display for RouteConflicts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Found {} conflicting route registrations:", self.0.len())?;
+        for conflict in self.0.iter() {
+            writeln!(f, "  - {conflict}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for RouteConflicts {}
+
+/// Look for routes that Pavex would reject, or that could never be reached, before the Blueprint
+/// gets to code generation.
+pub fn check_route_conflicts(routes: &[RegisteredRoute]) -> Result<(), RouteConflicts> {
+    let mut conflicts = Vec::new();
+    for (i, first) in routes.iter().enumerate() {
+        for second in routes[i + 1..].iter() {
+            if let Some(conflict) = conflict_between(first, second) {
+                conflicts.push(conflict);
+            }
+        }
+    }
+    if conflicts.is_empty() {
+        Ok(())
+    } else {
+        Err(RouteConflicts(conflicts))
+    }
+}
+
+fn conflict_between(first: &RegisteredRoute, second: &RegisteredRoute) -> Option<RouteConflict> {
+    let same_method = first.method == second.method;
+    if same_method && first.path == second.path {
+        return Some(RouteConflict::Duplicate(first.clone(), second.clone()));
+    }
+
+    let first_segments = first.path.split('/').collect::<Vec<_>>();
+    let second_segments = second.path.split('/').collect::<Vec<_>>();
+    let mut mismatch = None;
+    let mut catch_all = None;
+    let mut diverged = false;
+    for (a, b) in first_segments.iter().zip(second_segments.iter()) {
+        match (param_kind(a), param_kind(b)) {
+            (Some(a_kind), Some(b_kind)) if a_kind == b_kind => {
+                mismatch = mismatch.or_else(|| (a != b).then(|| (a.to_string(), b.to_string())));
+            }
+            // A catch-all takes every request a param in the same place would, and the rest of
+            // the other route with it
+            (Some('*'), Some(_)) => {
+                catch_all = Some((second, first));
+                break;
+            }
+            (Some(_), Some('*')) => {
+                catch_all = Some((first, second));
+                break;
+            }
+            // The paths go different ways from here, so the router can tell them apart. Static
+            // segments win over params, so a catch-all doesn't shadow them
+            _ if a != b => {
+                diverged = true;
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    if let Some((route, shadowed_by)) = catch_all.filter(|_| same_method) {
+        return Some(RouteConflict::Unreachable {
+            route: route.clone(),
+            shadowed_by: shadowed_by.clone(),
+        });
+    }
+
+    // The router shares the path up to where they diverge, so params in it need the same name
+    let (first_param, second_param) = mismatch?;
+    if !diverged && same_method && first_segments.len() == second_segments.len() {
+        return Some(RouteConflict::Unreachable {
+            route: second.clone(),
+            shadowed_by: first.clone(),
+        });
+    }
+    Some(RouteConflict::ParamNameMismatch {
+        first: first.clone(),
+        second: second.clone(),
+        first_param,
+        second_param,
+    })
+}
+
+/// The kind of param segment (`:` or `*`), or `None` for a static segment.
+fn param_kind(segment: &str) -> Option<char> {
+    segment
+        .chars()
+        .next()
+        .filter(|kind| matches!(kind, ':' | '*'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Found = (&'static str, &'static str, &'static str);
+
+    /// The kind of conflict found, with the paths of the routes it's between
+    fn summary(conflict: Option<RouteConflict>) -> Option<(&'static str, String, String)> {
+        Some(match conflict? {
+            RouteConflict::Duplicate(first, second) => ("duplicate", first.path, second.path),
+            RouteConflict::ParamNameMismatch { first, second, .. } => {
+                ("param name mismatch", first.path, second.path)
+            }
+            RouteConflict::Unreachable { route, shadowed_by } => {
+                ("unreachable", route.path, shadowed_by.path)
+            }
+        })
+    }
+
+    #[test]
+    fn conflicts_between_two_routes() {
+        let cases: &[(Method, &str, Method, &str, Option<Found>)] = &[
+            (
+                Method::GET,
+                "/about",
+                Method::GET,
+                "/about",
+                Some(("duplicate", "/about", "/about")),
+            ),
+            (Method::GET, "/about", Method::POST, "/about", None),
+            (Method::GET, "/post/:id", Method::GET, "/post/new", None),
+            (Method::GET, "/user/:id", Method::GET, "/post/:slug", None),
+            (
+                Method::GET,
+                "/post/:id",
+                Method::GET,
+                "/post/:slug",
+                Some(("unreachable", "/post/:slug", "/post/:id")),
+            ),
+            (
+                Method::GET,
+                "/post/:id",
+                Method::POST,
+                "/post/:slug",
+                Some(("param name mismatch", "/post/:id", "/post/:slug")),
+            ),
+            (
+                Method::GET,
+                "/post/:id/edit",
+                Method::GET,
+                "/post/:slug/comments",
+                Some((
+                    "param name mismatch",
+                    "/post/:id/edit",
+                    "/post/:slug/comments",
+                )),
+            ),
+            (
+                Method::GET,
+                "/files/*path",
+                Method::GET,
+                "/files/*rest",
+                Some(("unreachable", "/files/*rest", "/files/*path")),
+            ),
+            (
+                Method::GET,
+                "/files/*path",
+                Method::GET,
+                "/files/:name",
+                Some(("unreachable", "/files/:name", "/files/*path")),
+            ),
+            (
+                Method::GET,
+                "/files/:name/edit",
+                Method::GET,
+                "/files/*path",
+                Some(("unreachable", "/files/:name/edit", "/files/*path")),
+            ),
+            (
+                Method::GET,
+                "/files/*path",
+                Method::POST,
+                "/files/:name",
+                None,
+            ),
+            (
+                Method::GET,
+                "/files/*path",
+                Method::GET,
+                "/files/readme",
+                None,
+            ),
+            (Method::GET, "/api/greet/:name", Method::GET, "/*path", None),
+        ];
+        for (first_method, first_path, second_method, second_path, expected) in cases {
+            let first = RegisteredRoute::new(first_method.clone(), *first_path, RouteOrigin::Pavex);
+            let second =
+                RegisteredRoute::new(second_method.clone(), *second_path, RouteOrigin::Leptos);
+            let expected =
+                expected.map(|(kind, route, other)| (kind, route.to_string(), other.to_string()));
+            assert_eq!(
+                summary(conflict_between(&first, &second)),
+                expected,
+                "{first} and {second}"
+            );
+        }
+    }
+
+    #[test]
+    fn example_routes_have_no_conflicts() {
+        let routes = [
+            RegisteredRoute::new(Method::GET, "/", RouteOrigin::Leptos),
+            RegisteredRoute::new(Method::GET, "/about", RouteOrigin::Leptos),
+            RegisteredRoute::new(Method::GET, "/blocking", RouteOrigin::Leptos),
+            RegisteredRoute::new(Method::POST, "/api/greet", RouteOrigin::ServerFn),
+            RegisteredRoute::new(Method::POST, "/api/redirect_to", RouteOrigin::ServerFn),
+            RegisteredRoute::new(Method::GET, "/api/ping", RouteOrigin::Pavex),
+            RegisteredRoute::new(Method::GET, "/api/greet/:name", RouteOrigin::Pavex),
+            RegisteredRoute::new(Method::GET, "/*path", RouteOrigin::Pavex),
+        ];
+        assert!(check_route_conflicts(&routes).is_ok());
+    }
+
+    #[test]
+    fn every_conflict_is_reported() {
+        let routes = [
+            RegisteredRoute::new(Method::GET, "/post/:id", RouteOrigin::Leptos),
+            RegisteredRoute::new(Method::GET, "/post/:id", RouteOrigin::Pavex),
+            RegisteredRoute::new(Method::GET, "/post/:slug", RouteOrigin::Pavex),
+        ];
+        let conflicts = check_route_conflicts(&routes).unwrap_err();
+        assert_eq!(conflicts.0.len(), 3);
+    }
+}
@@ -0,0 +1,233 @@
+use crate::PavexRouteListing;
+use std::fmt;
+use std::sync::Arc;
+
+type RoutePredicate = Arc<dyn Fn(&PavexRouteListing) -> bool + Send + Sync>;
+
+/// A route to leave out of the generated route list, so it can be handled by a hand-written Pavex
+/// handler instead. Paths are matched against the Pavex path of each route, e.g. `/post/:id`, and
+/// a route with optional params is excluded if any of its variants matches.
+#[derive(Clone)]
+pub enum RouteExclusion {
+    /// Exclude the route with exactly this path.
+    Path(String),
+    /// Exclude the route at this path and every route below it. `/admin` matches `/admin` and
+    /// `/admin/users`, but not `/administrator`.
+    Prefix(String),
+    /// Exclude every route whose path matches this pattern. `*` matches anything within one
+    /// segment and `**` matches any number of segments, so `/admin/*` matches `/admin/users` and
+    /// `/admin/**` matches `/admin/users/:id` too.
+    Glob(String),
+    /// Exclude every route this returns `true` for.
+    Predicate(RoutePredicate),
+}
+
+impl RouteExclusion {
+    pub fn path(path: impl Into<String>) -> Self {
+        RouteExclusion::Path(path.into())
+    }
+
+    pub fn prefix(prefix: impl Into<String>) -> Self {
+        RouteExclusion::Prefix(prefix.into())
+    }
+
+    pub fn glob(pattern: impl Into<String>) -> Self {
+        RouteExclusion::Glob(pattern.into())
+    }
+
+    pub fn predicate(
+        predicate: impl Fn(&PavexRouteListing) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        RouteExclusion::Predicate(Arc::new(predicate))
+    }
+
+    /// Whether this exclusion matches the route.
+    pub fn matches(&self, listing: &PavexRouteListing) -> bool {
+        match self {
+            RouteExclusion::Predicate(predicate) => predicate(listing),
+            _ => self.matches_path(listing.path()),
+        }
+    }
+
+    fn matches_path(&self, path: &str) -> bool {
+        match self {
+            RouteExclusion::Path(excluded) => excluded == path,
+            RouteExclusion::Prefix(prefix) => {
+                let prefix = prefix.trim_end_matches('/');
+                prefix.is_empty()
+                    || path
+                        .strip_prefix(prefix)
+                        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+            }
+            RouteExclusion::Glob(pattern) => {
+                let pattern = pattern.split('/').collect::<Vec<_>>();
+                let path = path.split('/').collect::<Vec<_>>();
+                glob_segments(&pattern, &path)
+            }
+            RouteExclusion::Predicate(_) => false,
+        }
+    }
+}
+
+impl From<String> for RouteExclusion {
+    fn from(path: String) -> Self {
+        RouteExclusion::Path(path)
+    }
+}
+
+impl From<&str> for RouteExclusion {
+    fn from(path: &str) -> Self {
+        RouteExclusion::Path(path.to_string())
+    }
+}
+
+impl fmt::Debug for RouteExclusion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RouteExclusion::Path(path) => f.debug_tuple("Path").field(path).finish(),
+            RouteExclusion::Prefix(prefix) => f.debug_tuple("Prefix").field(prefix).finish(),
+            RouteExclusion::Glob(pattern) => f.debug_tuple("Glob").field(pattern).finish(),
+            RouteExclusion::Predicate(_) => f.write_str("Predicate(..)"),
+        }
+    }
+}
+
+/// Drop the routes matched by any of the exclusions, warning about exclusions that match nothing,
+/// since that's usually a typo. This runs before any of the route list is handed out, so excluded
+/// routes never get static data or Pavex routes.
+pub(crate) fn exclude_routes(routes: &mut Vec<PavexRouteListing>, exclusions: &[RouteExclusion]) {
+    let mut matched = vec![false; exclusions.len()];
+    routes.retain(|listing| {
+        let mut keep = true;
+        for (exclusion, matched) in exclusions.iter().zip(matched.iter_mut()) {
+            if exclusion.matches(listing) {
+                *matched = true;
+                keep = false;
+            }
+        }
+        keep
+    });
+    for (exclusion, matched) in exclusions.iter().zip(matched) {
+        if !matched {
+            tracing::warn!("route exclusion {exclusion:?} didn't match any route");
+        }
+    }
+}
+
+fn glob_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| glob_segments(rest, &path[skip..])),
+        Some((segment, rest)) => match path.split_first() {
+            Some((path_segment, path_rest)) => {
+                glob_segment(segment.as_bytes(), path_segment.as_bytes())
+                    && glob_segments(rest, path_rest)
+            }
+            None => false,
+        },
+    }
+}
+
+/// Match a single path segment, where `*` matches any run of characters.
+fn glob_segment(pattern: &[u8], segment: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => segment.is_empty(),
+        Some((b'*', rest)) => (0..=segment.len()).any(|skip| glob_segment(rest, &segment[skip..])),
+        Some((c, rest)) => segment
+            .split_first()
+            .is_some_and(|(s, segment_rest)| s == c && glob_segment(rest, segment_rest)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use leptos_router::{Method, SsrMode};
+
+    fn listing(path: &str) -> PavexRouteListing {
+        PavexRouteListing::new(path.to_string(), SsrMode::OutOfOrder, [Method::Get], None)
+    }
+
+    fn remaining(paths: &[&str], exclusions: &[RouteExclusion]) -> Vec<String> {
+        let mut routes = paths.iter().copied().map(listing).collect();
+        exclude_routes(&mut routes, exclusions);
+        routes
+            .iter()
+            .map(|route| route.path().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn path_matches_exactly() {
+        let exclusion = RouteExclusion::from("/admin");
+        assert!(exclusion.matches(&listing("/admin")));
+        assert!(!exclusion.matches(&listing("/admin/users")));
+    }
+
+    #[test]
+    fn prefix_matches_whole_segments() {
+        let exclusion = RouteExclusion::prefix("/admin/");
+        assert!(exclusion.matches(&listing("/admin")));
+        assert!(exclusion.matches(&listing("/admin/users/:id")));
+        assert!(!exclusion.matches(&listing("/administrator")));
+        assert!(!exclusion.matches(&listing("/")));
+        assert!(RouteExclusion::prefix("/").matches(&listing("/anything")));
+    }
+
+    #[test]
+    fn glob_star_stays_within_a_segment() {
+        let exclusion = RouteExclusion::glob("/admin/*");
+        assert!(exclusion.matches(&listing("/admin/users")));
+        assert!(exclusion.matches(&listing("/admin/:id")));
+        assert!(!exclusion.matches(&listing("/admin")));
+        assert!(!exclusion.matches(&listing("/admin/users/:id")));
+
+        let exclusion = RouteExclusion::glob("/post-*/edit");
+        assert!(exclusion.matches(&listing("/post-draft/edit")));
+        assert!(!exclusion.matches(&listing("/page-draft/edit")));
+    }
+
+    #[test]
+    fn glob_double_star_spans_segments() {
+        let exclusion = RouteExclusion::glob("/admin/**");
+        assert!(exclusion.matches(&listing("/admin")));
+        assert!(exclusion.matches(&listing("/admin/users/:id")));
+        assert!(!exclusion.matches(&listing("/user/admin")));
+
+        let exclusion = RouteExclusion::glob("/**/edit");
+        assert!(exclusion.matches(&listing("/edit")));
+        assert!(exclusion.matches(&listing("/post/:id/edit")));
+        assert!(!exclusion.matches(&listing("/post/:id")));
+    }
+
+    #[test]
+    fn predicate_sees_the_listing() {
+        let exclusion = RouteExclusion::predicate(|listing| listing.path().contains(':'));
+        assert!(exclusion.matches(&listing("/post/:id")));
+        assert!(!exclusion.matches(&listing("/post")));
+    }
+
+    #[test]
+    fn routes_matched_by_any_exclusion_are_dropped() {
+        let exclusions = [
+            RouteExclusion::path("/about"),
+            RouteExclusion::prefix("/admin"),
+            RouteExclusion::glob("/api/*"),
+            RouteExclusion::predicate(|listing| listing.path().ends_with("/edit")),
+            RouteExclusion::path("/typo"),
+        ];
+        let paths = [
+            "/",
+            "/about",
+            "/admin/users",
+            "/api/greet",
+            "/api/greet/:name",
+            "/post/:id",
+            "/post/:id/edit",
+        ];
+        assert_eq!(
+            remaining(&paths, &exclusions),
+            ["/", "/api/greet/:name", "/post/:id"]
+        );
+    }
+}
@@ -1,16 +1,20 @@
 use crate::pavex_helpers::AdditionalContextServerFn;
 use crate::request_parts::RequestParts;
 use crate::response_options::ResponseOptions;
-use crate::{request::PavexRequest, response::PavexResponse};
+use crate::{request::PavexRequest, response::PavexResponse, PavexRouteIndex};
 use dashmap::DashMap;
 use leptos::prelude::{provide_context, ScopedFuture};
+use leptos::server_fn::error::{NoCustomError, ServerFnError};
 use leptos::server_fn::middleware::Service;
+use leptos::server_fn::response::Res;
 use leptos::server_fn::{codec::Encoding, initialize_server_fn_map, ServerFn, ServerFnTraitObj};
 use once_cell::sync::Lazy;
-use pavex::http::{HeaderName, Method as HttpMethod, StatusCode};
-use pavex::request::body::RawIncomingBody;
+use pavex::http::header::CONTENT_LENGTH;
+use pavex::http::{Method as HttpMethod, StatusCode};
+use pavex::request::body::{BodySizeLimit, RawIncomingBody};
 use pavex::request::RequestHead;
 use pavex::response::Response;
+use pavex::unit::ByteUnit;
 use url::Url;
 
 #[allow(unused)] // used by server integrations
@@ -19,6 +23,57 @@ type LazyServerFnMap<Req, Res> = Lazy<DashMap<&'static str, ServerFnTraitObj<Req
 static REGISTERED_SERVER_FUNCTIONS: LazyServerFnMap<PavexRequest, PavexResponse> =
     initialize_server_fn_map!(PavexRequest, PavexResponse);
 
+static SERVER_FN_BODY_LIMITS: Lazy<DashMap<&'static str, BodySizeLimit>> = Lazy::new(|| {
+    leptos::server_fn::inventory::iter::<ServerFnBodyLimit>
+        .into_iter()
+        .map(|limit| {
+            (
+                limit.path,
+                BodySizeLimit::Enabled {
+                    max_size: limit.max_size.into(),
+                },
+            )
+        })
+        .collect()
+});
+
+/// A body limit declared with `#[server(body_limit = "10MB")]`, collected when the first request
+/// comes in. Use [`PavexRouteIndex::with_server_fn_body_limit`] to set one by hand.
+#[doc(hidden)]
+pub struct ServerFnBodyLimit {
+    pub path: &'static str,
+    pub max_size: usize,
+}
+
+leptos::server_fn::inventory::collect!(ServerFnBodyLimit);
+
+/// The body limit the server function at `path` was declared with, if it set one.
+pub(crate) fn declared_body_limit(path: &str) -> Option<BodySizeLimit> {
+    SERVER_FN_BODY_LIMITS.get(path).map(|limit| *limit)
+}
+
+/// Whether the request says up front that its body is over `max_size`, so it can be turned down
+/// without reading any of it.
+fn declared_body_too_large(req_head: &RequestHead, max_size: ByteUnit) -> bool {
+    req_head
+        .headers
+        .get(CONTENT_LENGTH)
+        .and_then(|len| len.to_str().ok())
+        .and_then(|len| len.parse::<u64>().ok())
+        .is_some_and(|len| len > max_size.as_u64())
+}
+
+/// A `413 Payload Too Large` with the error serialized the way the server function client expects.
+fn body_too_large(path: &str, max_size: ByteUnit) -> Response {
+    let err = ServerFnError::<NoCustomError>::Request(format!(
+        "The request body is larger than the limit of {} bytes",
+        max_size.as_u64()
+    ));
+    let mut res = <PavexResponse as Res<NoCustomError>>::error_response(path, &err).0;
+    *res.status_mut() = StatusCode::PAYLOAD_TOO_LARGE;
+    res
+}
+
 /// Explicitly register a server function. This is only necessary if you are
 /// running the server in a WASM environment (or a rare environment that the
 /// `inventory`) crate doesn't support. Spin is one of those environments
@@ -43,62 +98,89 @@ pub fn server_fn_paths() -> impl Iterator<Item = (&'static str, HttpMethod)> {
         .iter()
         .map(|item| (item.path(), item.method()))
 }
+
+/// The server functions one app answers. Every `#[server]` function in the binary is registered
+/// in the same place, so when a Blueprint holds more than one app, each one picks its own by path,
+/// usually a prefix they all share. Give the same registry to
+/// [`LeptosAppHandlers`](crate::leptos_routes::LeptosAppHandlers), so only those are routed to
+/// the app, and to [`PavexRouteIndex::with_server_fns`], so only those are run by it. By default
+/// an app owns every server function.
+#[derive(Clone, Copy, Debug)]
+pub struct ServerFnRegistry {
+    owns: fn(&str) -> bool,
+}
+
+impl ServerFnRegistry {
+    /// Only the server functions whose path `owns` returns true for. The path is the one the
+    /// server function was declared with, without the app's base path.
+    pub const fn new(owns: fn(&str) -> bool) -> Self {
+        Self { owns }
+    }
+
+    /// Whether the server function at `path` belongs to the app.
+    pub fn owns(&self, path: &str) -> bool {
+        (self.owns)(path)
+    }
+
+    /// The paths of the server functions that belong to the app.
+    pub fn paths(&self) -> impl Iterator<Item = (&'static str, HttpMethod)> + '_ {
+        server_fn_paths().filter(|(path, _)| self.owns(path))
+    }
+
+    /// Returns the server function at the given path, if it belongs to the app.
+    pub fn get(&self, path: &str) -> Option<ServerFnTraitObj<PavexRequest, PavexResponse>> {
+        get_server_fn_by_path(path).filter(|server_fn| self.owns(server_fn.path()))
+    }
+}
+
+impl Default for ServerFnRegistry {
+    fn default() -> Self {
+        Self::new(|_| true)
+    }
+}
 pub async fn handle_server_fns(
+    routes: &PavexRouteIndex,
     req_head: RequestHead,
     req_body: RawIncomingBody,
     context: AdditionalContextServerFn,
 ) -> Response {
-    handle_server_fns_with_context(req_head, req_body, context).await
+    handle_server_fns_with_context(routes, req_head, req_body, context).await
 }
 pub async fn handle_server_fns_with_context(
+    routes: &PavexRouteIndex,
     req_head: RequestHead,
     req_body: RawIncomingBody,
     context: AdditionalContextServerFn,
 ) -> Response {
     let pq = req_head.target.path_and_query().unwrap();
-    match crate::server_fn::get_server_fn_by_path(pq.as_str()) {
+    // Server functions are compiled with their path under the app, without the base path
+    let server_fn = routes
+        .base_path()
+        .strip(pq.as_str())
+        .and_then(|path| routes.server_fns().get(&path));
+    match server_fn {
         Some(lepfn) => {
+            let body_limit = routes.body_limit_for(lepfn.path());
+            if let BodySizeLimit::Enabled { max_size } = body_limit {
+                if declared_body_too_large(&req_head, max_size) {
+                    return body_too_large(lepfn.path(), max_size);
+                }
+            }
             let owner = context.owner();
             let blah = owner.with(|| {
                 ScopedFuture::new(async move {
                     let req_parts = RequestParts::new_from_req(&req_head);
                     provide_context(req_parts.clone());
+                    // So server functions can reach their app, e.g. to invalidate its pages
+                    crate::provide_app_contexts(routes);
                     let res_options = ResponseOptions::default();
                     provide_context(res_options.clone());
-                    let pavex_req = PavexRequest::new_from_req(req_head, req_body);
-                    let (mut pavex_res, req_parts, res_options) =
-                        (lepfn.clone().run(pavex_req).await, req_parts, res_options);
-                    // If the Accept header contains text/html, then this is a request from
-                    // a regular html form, so we should set up a redirect to either the referrer
-                    // or the user specified location
-
-                    let req_headers = req_parts.headers();
-                    let accepts = req_headers.get("Accept");
-                    let accepts_html_bool = match accepts {
-                        Some(h) => match h.to_str().unwrap().contains("text/html") {
-                            true => true,
-                            false => false,
-                        },
-                        None => false,
-                    };
-
-                    if accepts_html_bool {
-                        let referrer = &req_headers.get("Referer");
-                        let location = &req_headers.get("Location");
-                        if location.is_none() {
-                            if let Some(referrer) = *referrer {
-                                res_options.insert_header(
-                                    HeaderName::from_static("location"),
-                                    referrer.to_owned(),
-                                );
-                            }
-                        }
-                        // Set status
-                        if res_options.status().is_none() {
-                            res_options.set_status(StatusCode::FOUND);
-                        }
-                    }
-
+                    let pavex_req =
+                        PavexRequest::new_from_req(req_head, req_body).with_body_limit(body_limit);
+                    // Plain form posts are sent back to their referrer by the server function
+                    // itself, see `PavexRequest::accepts`. A location set with `redirect()` is
+                    // in the response options, which are applied last, so it wins.
+                    let mut pavex_res = lepfn.clone().run(pavex_req).await;
                     pavex_res
                         .0
                         .headers_mut()
@@ -107,6 +189,12 @@ pub async fn handle_server_fns_with_context(
                     if let Some(status) = res_options.status() {
                         pavex_res.0 = pavex_res.0.set_status(status);
                     }
+                    #[cfg(feature = "compression")]
+                    let pavex_res = PavexResponse(crate::compression::compress_response(
+                        routes.compression(),
+                        crate::compression::negotiate(routes.compression(), req_parts.headers()),
+                        pavex_res.0,
+                    ));
                     pavex_res.0
                 })
             });
@@ -139,3 +227,62 @@ pub fn get_server_fn_by_path(path: &str) -> Option<ServerFnTraitObj<PavexRequest
         .get_mut(url.path())
         .map(|f| f.clone())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::static_routes::static_request_head;
+    use pavex::http::HeaderValue;
+
+    fn limit(max_size: usize) -> BodySizeLimit {
+        BodySizeLimit::Enabled {
+            max_size: max_size.into(),
+        }
+    }
+
+    fn max_size(limit: BodySizeLimit) -> Option<u64> {
+        match limit {
+            BodySizeLimit::Enabled { max_size } => Some(max_size.as_u64()),
+            BodySizeLimit::Disabled => None,
+        }
+    }
+
+    #[test]
+    fn bodies_declared_over_the_limit_are_a_413() {
+        let mut req_head = static_request_head("/api/upload").unwrap();
+        req_head
+            .headers
+            .insert(CONTENT_LENGTH, HeaderValue::from_static("2048"));
+        assert!(declared_body_too_large(&req_head, 1024usize.into()));
+        assert!(!declared_body_too_large(&req_head, 2048usize.into()));
+
+        let res = body_too_large("/api/upload", 1024usize.into());
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[test]
+    fn bodies_without_a_length_are_left_to_the_stream() {
+        let req_head = static_request_head("/api/upload").unwrap();
+        assert!(!declared_body_too_large(&req_head, 0usize.into()));
+    }
+
+    #[test]
+    fn server_fns_have_no_body_limit_by_default() {
+        let routes = PavexRouteIndex::default();
+        assert_eq!(max_size(routes.body_limit_for("/api/anything")), None);
+    }
+
+    #[test]
+    fn body_limits_set_by_hand_come_before_declared_ones_and_the_default() {
+        SERVER_FN_BODY_LIMITS.insert("/api/declared", limit(10));
+        SERVER_FN_BODY_LIMITS.insert("/api/overridden", limit(10));
+        let mut routes = PavexRouteIndex::default().with_body_limit(limit(100));
+        routes
+            .server_fn_body_limits
+            .insert("/api/overridden", limit(1));
+
+        assert_eq!(max_size(routes.body_limit_for("/api/overridden")), Some(1));
+        assert_eq!(max_size(routes.body_limit_for("/api/declared")), Some(10));
+        assert_eq!(max_size(routes.body_limit_for("/api/other")), Some(100));
+    }
+}
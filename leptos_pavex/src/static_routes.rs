@@ -0,0 +1,961 @@
+use crate::page_cache::is_private;
+use crate::pavex_helpers::{AdditionalContextComponent, AppFunction, AppRenderer};
+use crate::request_parts::RequestParts;
+use crate::response::build_response;
+use crate::response_options::ResponseOptions;
+use crate::{
+    current_meta_context, provide_post_contexts, PavexRouteIndex, PavexRouteList, PavexRouteListing,
+};
+use dashmap::mapref::entry::Entry;
+use dashmap::DashMap;
+use futures::StreamExt;
+use leptos::config::LeptosOptions;
+use leptos::tachys::view::RenderHtml;
+use leptos_integration_utils::PinnedStream;
+use leptos_router::StaticMode;
+use pavex::http::header::{CONTENT_TYPE, SET_COOKIE};
+use pavex::http::{HeaderMap, HeaderName, HeaderValue, StatusCode};
+use pavex::request::RequestHead;
+use pavex::response::body::raw::Full;
+use pavex::response::Response;
+use reactive_graph::computed::ScopedFuture;
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Once};
+use std::time::{Duration, Instant};
+use url::Url;
+
+/// How many incremental pages are kept on disk unless
+/// [`PavexRouteIndex::with_max_incremental_pages`] says otherwise
+pub const DEFAULT_MAX_INCREMENTAL_PAGES: usize = 1000;
+
+/// The values a static route is rendered with for each of its params, keyed by param name. A route
+/// is rendered once for every combination of them.
+pub type StaticParamsMap = HashMap<String, Vec<String>>;
+
+/// The params to render static routes with, keyed by the Pavex path of the route, e.g.
+/// `/post/:id`. The Leptos revision this crate is built against can't attach static data to a
+/// route, so routes with params only get rendered for the values given here.
+pub type StaticParams = HashMap<String, StaticParamsMap>;
+
+/// The incrementally generated pages of one app, and how long they're kept. Each app's
+/// [`PavexRouteIndex`] has its own, set up with its `with_*` methods. Clones share the stored
+/// pages.
+#[derive(Clone, Debug)]
+pub(crate) struct IncrementalPages {
+    store: Arc<PageStore>,
+    default_revalidation_window: Option<Duration>,
+    /// How long an incremental page is served before it gets regenerated, keyed by route path
+    revalidation_windows: HashMap<String, Duration>,
+    max_pages: usize,
+}
+
+#[derive(Debug, Default)]
+struct PageStore {
+    /// Incrementally generated pages that are currently stored on disk, keyed by request path
+    pages: DashMap<String, IncrementalPage>,
+    /// How many times each path has been invalidated. A render that started before an
+    /// invalidation sees a different generation when it finishes, and throws its page away
+    /// instead of storing it.
+    generations: DashMap<String, u64>,
+}
+
+impl Default for IncrementalPages {
+    fn default() -> Self {
+        Self {
+            store: Arc::default(),
+            default_revalidation_window: None,
+            revalidation_windows: HashMap::new(),
+            max_pages: DEFAULT_MAX_INCREMENTAL_PAGES,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct IncrementalPage {
+    file_path: PathBuf,
+    generated_at: Instant,
+    regenerating: bool,
+}
+
+impl AppRenderer {
+    /// Render `path` from a bare GET request, under a fresh context owner.
+    async fn render_static(&self, path: &str) -> io::Result<RenderedPage> {
+        let req_head = static_request_head(path)?;
+        let (context, app) = self.build(&req_head);
+        Ok(render_static_page(req_head, context, app).await)
+    }
+}
+
+/// A page rendered into a complete HTML document, with the status and headers the app set through
+/// [`ResponseOptions`] while rendering it.
+#[derive(Clone, Debug)]
+pub(crate) struct RenderedPage {
+    pub(crate) status: Option<StatusCode>,
+    pub(crate) headers: HeaderMap,
+    pub(crate) html: String,
+}
+
+impl RenderedPage {
+    /// Pages that set an error status shouldn't be stored, or every visitor would get the error.
+    /// Neither should pages that set a cookie or are marked `Cache-Control: private` or
+    /// `no-store`, since their headers are replayed to every visitor.
+    fn is_cacheable(&self) -> bool {
+        self.status.map_or(true, |status| status.is_success())
+            && !self.headers.contains_key(SET_COOKIE)
+            && !is_private(&self.headers)
+    }
+
+    fn into_response(self) -> Response {
+        let mut res = html_response(
+            self.status.unwrap_or(StatusCode::OK),
+            self.html.into_bytes(),
+        );
+        replay_headers(&mut res, &self.headers);
+        res
+    }
+}
+
+/// Renders every route marked with `StaticMode::Upfront` to an HTML file under the site root, so
+/// `render_route` can serve them without running the app. Routes with params are rendered once for
+/// every combination of the values `static_params` has for them. Call this at startup or from a
+/// build script, after generating the route list with `generate_route_list_with_ssg`, or with a
+/// route list read from an artifact, whose static routes already know their paths.
+///
+/// Pavex builds the context and the app per request, so you need to give this the same
+/// constructors you registered in your Blueprint.
+pub async fn build_static_routes<C, A>(
+    options: &LeptosOptions,
+    routes: &PavexRouteList,
+    static_params: &StaticParams,
+    context_fn: C,
+    app_fn: A,
+) -> io::Result<()>
+where
+    C: Fn(&RequestHead) -> AdditionalContextComponent,
+    A: Fn(&AdditionalContextComponent, &RequestHead) -> AppFunction,
+{
+    // The site root has to exist for pages to be checked against it
+    std::fs::create_dir_all(&*options.site_root)?;
+    for listing in routes
+        .iter()
+        .filter(|r| r.static_mode() == Some(StaticMode::Upfront))
+    {
+        for path in static_paths(listing, static_params) {
+            let req_head = static_request_head(&path)?;
+            let context = context_fn(&req_head);
+            let app = app_fn(&context, &req_head);
+            let page = render_static_page(req_head, context, app).await;
+            if !page.is_cacheable() {
+                tracing::warn!(
+                    "Rendering the static route {path} returned an error status, \
+                     it will be rendered on every request instead"
+                );
+                continue;
+            }
+            match static_file_path(options, &path) {
+                Some(file_path) => write_static_page(&file_path, &page)?,
+                None => tracing::warn!(
+                    "The static route {path} would be written outside of the site root, \
+                     it will be rendered on every request instead"
+                ),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The paths a static route is rendered at. A route with params is rendered once for every
+/// combination of the values in `static_params`, unless its paths were already resolved when the
+/// route list was written as an artifact.
+pub(crate) fn static_paths(
+    listing: &PavexRouteListing,
+    static_params: &StaticParams,
+) -> Vec<String> {
+    if let Some(paths) = &listing.static_paths {
+        return paths.clone();
+    }
+    expand_static_paths(listing.path(), static_params.get(listing.path()))
+}
+
+/// Returns a response with the prerendered page for this request, if the route is static and
+/// the page has been built.
+pub fn serve_static_page(
+    options: &LeptosOptions,
+    listing: &PavexRouteListing,
+    req_head: &RequestHead,
+) -> Option<Response> {
+    if listing.static_mode() != Some(StaticMode::Upfront) {
+        return None;
+    }
+    read_static_page(&static_file_path(options, req_head.target.path())?)
+}
+
+/// Serve a route marked with `StaticMode::Incremental`. The first request for a path renders the
+/// page and stores it under the site root, and later requests get the stored copy. Once the
+/// page is older than its revalidation window, it's still served one more time while a fresh copy
+/// is rendered in the background. Pages are only tracked in memory, so they get rendered again
+/// after the server restarts, and only the
+/// [`with_max_incremental_pages`](PavexRouteIndex::with_max_incremental_pages) most recent ones are
+/// kept.
+///
+/// Stored pages are rendered by the app renderer on the route index from a bare GET for the path,
+/// under a fresh context owner, never from the visitor's request. Without one,
+/// every request is rendered live with the visitor's `context` and `app_fn` and nothing is stored.
+pub async fn render_incremental(
+    options: &LeptosOptions,
+    routes: &PavexRouteIndex,
+    listing: &PavexRouteListing,
+    req_head: RequestHead,
+    context: AdditionalContextComponent,
+    app_fn: AppFunction,
+) -> Response {
+    let path = req_head.target.path().to_string();
+    let Some(renderer) = routes.app_renderer() else {
+        static WARN_NO_RENDERER: Once = Once::new();
+        WARN_NO_RENDERER.call_once(|| {
+            tracing::warn!(
+                "Incremental routes need an AppRenderer on the route index to be stored, \
+                 they'll be rendered on every request instead"
+            )
+        });
+        return render_static_page(req_head, context, app_fn)
+            .await
+            .into_response();
+    };
+    // Paths that would end up outside the site root are rendered, but never stored
+    let Some(file_path) = static_file_path(options, &path) else {
+        return match renderer.render_static(&path).await {
+            Ok(page) => page.into_response(),
+            Err(_) => render_static_page(req_head, context, app_fn)
+                .await
+                .into_response(),
+        };
+    };
+
+    let pages = &routes.incremental_pages;
+    let stored = pages
+        .store
+        .pages
+        .get(&path)
+        .map(|page| (page.generated_at, page.regenerating));
+    if let Some((generated_at, regenerating)) = stored {
+        if let Some(res) = read_static_page(&file_path) {
+            let is_stale = pages
+                .revalidation_window(listing.path())
+                .is_some_and(|window| generated_at.elapsed() >= window);
+            if is_stale && !regenerating {
+                if let Some(mut page) = pages.store.pages.get_mut(&path) {
+                    page.regenerating = true;
+                }
+                let renderer = renderer.clone();
+                let pages = pages.clone();
+                let generation = pages.generation(&path);
+                any_spawner::Executor::spawn(async move {
+                    let stored = match renderer.render_static(&path).await {
+                        Ok(page) if page.is_cacheable() => {
+                            pages.store_page(&path, &file_path, &page, generation).await
+                        }
+                        Ok(_) => Ok(false),
+                        Err(e) => Err(e),
+                    };
+                    let stored = match stored {
+                        Ok(stored) => stored,
+                        Err(e) => {
+                            tracing::warn!("Failed to regenerate the page for {path}: {e}");
+                            false
+                        }
+                    };
+                    if !stored {
+                        if let Some(mut page) = pages.store.pages.get_mut(&path) {
+                            page.regenerating = false;
+                        }
+                    }
+                });
+            }
+            return res;
+        }
+    }
+
+    let generation = pages.generation(&path);
+    let page = match renderer.render_static(&path).await {
+        Ok(page) => page,
+        Err(_) => {
+            return render_static_page(req_head, context, app_fn)
+                .await
+                .into_response()
+        }
+    };
+    if page.is_cacheable() {
+        if let Err(e) = pages.store_page(&path, &file_path, &page, generation).await {
+            tracing::warn!("Failed to store the page for {path}: {e}");
+        }
+    }
+    page.into_response()
+}
+
+impl PavexRouteIndex {
+    /// Mark the incrementally generated page at `path` as outdated, so the next request for it
+    /// renders a fresh copy. Call this after a server function changes the data a page depends
+    /// on, the index is provided to server functions as context. A copy that was already being
+    /// rendered when this is called gets thrown away.
+    pub fn invalidate_static_path(&self, path: &str) {
+        let store = &self.incremental_pages.store;
+        *store.generations.entry(path.to_string()).or_insert(0) += 1;
+        if let Some((_, page)) = store.pages.remove(path) {
+            remove_static_page(&page.file_path);
+        }
+    }
+
+    /// Set how long incremental pages are served before being regenerated. By default pages are
+    /// kept until they're invalidated with
+    /// [`invalidate_static_path`](Self::invalidate_static_path).
+    pub fn with_revalidation_window(mut self, window: Duration) -> Self {
+        self.incremental_pages.default_revalidation_window = Some(window);
+        self
+    }
+
+    /// Set how long pages for a single route are served before being regenerated, overriding the
+    /// default. The route is given as its Pavex path, e.g. `/post/:id`.
+    pub fn with_route_revalidation_window(
+        mut self,
+        route: impl Into<String>,
+        window: Duration,
+    ) -> Self {
+        self.incremental_pages
+            .revalidation_windows
+            .insert(route.into(), window);
+        self
+    }
+
+    /// Set how many incremental pages are stored at most, defaulting to
+    /// [`DEFAULT_MAX_INCREMENTAL_PAGES`]. Each value of a route param is its own page, so once the
+    /// limit is reached the oldest page is deleted to make room. Set it to 0 to never store them.
+    pub fn with_max_incremental_pages(mut self, max: usize) -> Self {
+        self.incremental_pages.max_pages = max;
+        self
+    }
+}
+
+impl IncrementalPages {
+    fn revalidation_window(&self, route: &str) -> Option<Duration> {
+        self.revalidation_windows
+            .get(route)
+            .copied()
+            .or(self.default_revalidation_window)
+    }
+
+    fn generation(&self, path: &str) -> u64 {
+        self.store
+            .generations
+            .get(path)
+            .map_or(0, |generation| *generation)
+    }
+
+    /// Write the page and start tracking it, unless `path` was invalidated since the render
+    /// started. Returns whether the page was stored. The files are written on a blocking thread,
+    /// without holding on to any of the maps.
+    async fn store_page(
+        &self,
+        path: &str,
+        file_path: &Path,
+        page: &RenderedPage,
+        generation: u64,
+    ) -> io::Result<bool> {
+        if self.generation(path) != generation {
+            return Ok(false);
+        }
+        let Some(evicted) = self.make_room_for(path) else {
+            return Ok(false);
+        };
+        let (target, page) = (file_path.to_path_buf(), page.clone());
+        blocking(move || {
+            evicted
+                .iter()
+                .for_each(|evicted| remove_static_page(evicted));
+            write_static_page(&target, &page)
+        })
+        .await?;
+
+        // Holding the entry keeps `invalidate_static_path` out until the page is tracked
+        let entry = self.store.generations.entry(path.to_string());
+        let current = match &entry {
+            Entry::Occupied(entry) => *entry.get(),
+            Entry::Vacant(_) => 0,
+        };
+        if current != generation {
+            // Invalidated while it was being written
+            drop(entry);
+            let target = file_path.to_path_buf();
+            blocking(move || {
+                remove_static_page(&target);
+                Ok(())
+            })
+            .await?;
+            return Ok(false);
+        }
+        self.store.pages.insert(
+            path.to_string(),
+            IncrementalPage {
+                file_path: file_path.to_path_buf(),
+                generated_at: Instant::now(),
+                regenerating: false,
+            },
+        );
+        drop(entry);
+        Ok(true)
+    }
+
+    /// Stop tracking the oldest pages until there's room to store `path`, and return their files
+    /// for the caller to delete. Returns `None` if nothing can be stored at all.
+    fn make_room_for(&self, path: &str) -> Option<Vec<PathBuf>> {
+        if self.max_pages == 0 {
+            return None;
+        }
+        let pages = &self.store.pages;
+        let mut evicted = Vec::new();
+        while pages.len() >= self.max_pages && !pages.contains_key(path) {
+            let oldest = pages
+                .iter()
+                .min_by_key(|page| page.generated_at)
+                .map(|page| page.key().clone());
+            let Some(oldest) = oldest else {
+                break;
+            };
+            if let Some((_, page)) = pages.remove(&oldest) {
+                evicted.push(page.file_path);
+            }
+        }
+        Some(evicted)
+    }
+}
+
+/// Run file system work where it won't hold up other requests: on tokio's blocking threads, or
+/// right here outside of a tokio runtime.
+#[cfg(feature = "ssr")]
+async fn blocking<T: Send + 'static>(
+    work: impl FnOnce() -> io::Result<T> + Send + 'static,
+) -> io::Result<T> {
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) => handle
+            .spawn_blocking(work)
+            .await
+            .map_err(io::Error::other)?,
+        Err(_) => work(),
+    }
+}
+
+#[cfg(not(feature = "ssr"))]
+async fn blocking<T>(work: impl FnOnce() -> io::Result<T>) -> io::Result<T> {
+    work()
+}
+
+fn html_response(status: StatusCode, html: Vec<u8>) -> Response {
+    Response::new(status)
+        .insert_header(
+            CONTENT_TYPE,
+            HeaderValue::from_static("text/html; charset=utf-8"),
+        )
+        .set_raw_body(Full::new(html.into()))
+}
+
+/// Put the headers the app set while rendering a page on the response, replacing any header with
+/// the same name.
+fn replay_headers(res: &mut Response, headers: &HeaderMap) {
+    let res_headers = res.headers_mut();
+    for name in headers.keys() {
+        res_headers.remove(name);
+        for value in headers.get_all(name) {
+            res_headers.append(name.clone(), value.clone());
+        }
+    }
+}
+
+/// The file a static page for `path` is written to. Pages are stored as `index.html` in a
+/// directory named after the path, the same layout `serve_files` expects.
+///
+/// Request paths aren't normalized, so this returns `None` for any path that could resolve
+/// outside of the site root: one with `.`, `..` or empty segments, or one that goes through a
+/// symlink pointing out of it.
+pub fn static_file_path(options: &LeptosOptions, path: &str) -> Option<PathBuf> {
+    let root = Path::new(&*options.site_root);
+    let mut file_path = root.to_path_buf();
+    let path = path.trim_matches('/');
+    if !path.is_empty() {
+        for segment in path.split('/') {
+            if matches!(segment, "" | "." | "..") || segment.contains('\\') {
+                return None;
+            }
+            file_path.push(segment);
+        }
+    }
+    file_path.push("index.html");
+
+    // Whatever part of the path already exists has to stay inside the root once resolved
+    let root = root.canonicalize().ok()?;
+    let existing = file_path.ancestors().find(|ancestor| ancestor.exists())?;
+    let existing = existing.canonicalize().ok()?;
+    existing.starts_with(&root).then_some(file_path)
+}
+
+/// The status and headers of a static page are stored next to it, so they can be replayed when
+/// the page is served. The file has the status on its first line, then one `name: value` line per
+/// header.
+fn headers_file_path(file_path: &Path) -> PathBuf {
+    file_path.with_file_name(HEADERS_FILE_NAME)
+}
+
+const HEADERS_FILE_NAME: &str = "index.headers";
+
+/// Whether `path` is where the status and headers of a static page are stored. They're only meant
+/// for replaying onto the page, so the file helpers never serve them.
+#[cfg(any(feature = "ssr", test))]
+pub(crate) fn is_page_metadata(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name == HEADERS_FILE_NAME)
+}
+
+fn write_static_page(file_path: &Path, page: &RenderedPage) -> io::Result<()> {
+    if let Some(parent) = file_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let status = page.status.unwrap_or(StatusCode::OK);
+    let mut headers = format!("{}\n", status.as_u16()).into_bytes();
+    for (name, value) in &page.headers {
+        // The content type is always set when the page is served
+        if name == CONTENT_TYPE {
+            continue;
+        }
+        headers.extend_from_slice(name.as_str().as_bytes());
+        headers.extend_from_slice(b": ");
+        headers.extend_from_slice(value.as_bytes());
+        headers.push(b'\n');
+    }
+    // Write the headers first, so the page is never served without them
+    write_atomically(&headers_file_path(file_path), &headers)?;
+    write_atomically(file_path, page.html.as_bytes())
+}
+
+/// Write to a temporary file and move it into place, so a page that's being served while it's
+/// regenerated is never read half written. Every write gets a temporary file of its own, so writes
+/// to the page and its headers, or two writes of the same page, never share one.
+fn write_atomically(path: &Path, contents: &[u8]) -> io::Result<()> {
+    static TMP_FILES: AtomicUsize = AtomicUsize::new(0);
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(format!(
+        ".{}.{}.tmp",
+        std::process::id(),
+        TMP_FILES.fetch_add(1, Ordering::Relaxed)
+    ));
+    let tmp_path = path.with_file_name(tmp_name);
+    if let Err(e) = std::fs::write(&tmp_path, contents) {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(e);
+    }
+    std::fs::rename(tmp_path, path)
+}
+
+fn read_static_page(file_path: &Path) -> Option<Response> {
+    let html = std::fs::read(file_path).ok()?;
+    let mut status = StatusCode::OK;
+    let mut headers = HeaderMap::new();
+    if let Ok(stored) = std::fs::read(headers_file_path(file_path)) {
+        let mut lines = stored
+            .split(|b| *b == b'\n')
+            .filter(|line| !line.is_empty());
+        if let Some(stored_status) = lines
+            .next()
+            .and_then(|line| std::str::from_utf8(line).ok())
+            .and_then(|line| line.parse::<u16>().ok())
+            .and_then(|code| StatusCode::from_u16(code).ok())
+        {
+            status = stored_status;
+        }
+        for line in lines {
+            let Some(colon) = line.iter().position(|b| *b == b':') else {
+                continue;
+            };
+            let name = HeaderName::from_bytes(&line[..colon]);
+            let value = HeaderValue::from_bytes(line[colon + 1..].trim_ascii_start());
+            if let (Ok(name), Ok(value)) = (name, value) {
+                headers.append(name, value);
+            }
+        }
+    }
+    let mut res = html_response(status, html);
+    replay_headers(&mut res, &headers);
+    Some(res)
+}
+
+fn remove_static_page(file_path: &Path) {
+    let _ = std::fs::remove_file(file_path);
+    let _ = std::fs::remove_file(headers_file_path(file_path));
+}
+
+/// Render the app for a single request into a complete HTML document, along with the status and
+/// headers the app set while rendering it.
+pub(crate) async fn render_static_page(
+    req_head: RequestHead,
+    context: AdditionalContextComponent,
+    app_fn: AppFunction,
+) -> RenderedPage {
+    let owner = context.owner().clone();
+    owner
+        .with(|| {
+            ScopedFuture::new(async move {
+                let res_options = ResponseOptions::default();
+                let meta_context = current_meta_context();
+                let additional_context = {
+                    let meta_context = meta_context.clone();
+                    let res_options = res_options.clone();
+                    move || {
+                        let path = req_head.target.path();
+                        let full_path = format!("http://leptos.dev{path}");
+                        let req_parts = RequestParts::new_from_req(&req_head);
+                        provide_post_contexts(&full_path, &meta_context, req_parts, res_options);
+                    }
+                };
+                let (owner, stream) =
+                    build_response(app_fn, meta_context, additional_context, |app, chunks| {
+                        Box::pin(async move {
+                            Box::pin(app.inner().to_html_stream_in_order().chain(chunks()))
+                                as PinnedStream<String>
+                        })
+                    });
+                let html = stream.await.collect::<String>().await;
+                drop(owner);
+
+                RenderedPage {
+                    status: res_options.status(),
+                    headers: res_options.headers(),
+                    html,
+                }
+            })
+        })
+        .await
+}
+
+/// Build a bare GET request for a path, to hand to the context and app constructors outside of a
+/// real request. Fails with [`io::ErrorKind::InvalidInput`] if `path` isn't a valid request path.
+pub(crate) fn static_request_head(path: &str) -> io::Result<RequestHead> {
+    let request = http::Request::builder().uri(path).body(()).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{path} is not a valid request path: {e}"),
+        )
+    })?;
+    Ok(request.into_parts().0.into())
+}
+
+/// Turn a Pavex path into every concrete path we need to render, filling in each `:param` and
+/// `*splat` with the values in the static params map. Values are percent-encoded, except for the
+/// slashes between the segments of a splat. Routes with params that have no values produce no
+/// paths.
+fn expand_static_paths(path: &str, params: Option<&StaticParamsMap>) -> Vec<String> {
+    let mut paths = vec![String::new()];
+    for segment in path.split('/').filter(|s| !s.is_empty()) {
+        let values = if let Some(name) = segment.strip_prefix(':') {
+            match params.and_then(|p| p.get(name)) {
+                Some(values) => values
+                    .iter()
+                    .map(|v| encode_segments([v.as_str()]))
+                    .collect(),
+                None => return Vec::new(),
+            }
+        } else if let Some(name) = segment.strip_prefix('*') {
+            match params.and_then(|p| p.get(name)) {
+                Some(values) => values
+                    .iter()
+                    .map(|v| encode_segments(v.trim_start_matches('/').split('/')))
+                    .collect(),
+                None => return Vec::new(),
+            }
+        } else {
+            vec![segment.to_string()]
+        };
+        paths = paths
+            .iter()
+            .flat_map(|p| values.iter().map(move |v| format!("{p}/{v}")))
+            .collect();
+    }
+    paths
+        .into_iter()
+        .map(|p| if p.is_empty() { "/".to_string() } else { p })
+        .collect()
+}
+
+/// Percent-encode each segment the way it's written in a URL path, and join them with slashes.
+fn encode_segments<'a>(segments: impl IntoIterator<Item = &'a str>) -> String {
+    let mut url = Url::parse("http://leptos.dev").expect("The base URL is valid");
+    url.path_segments_mut()
+        .expect("The base URL has a path")
+        .extend(segments);
+    url.path().trim_start_matches('/').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{pass_leptos_context, RouteType};
+    use futures::executor::block_on;
+    use http_body_util::BodyExt;
+    use leptos::prelude::*;
+    use leptos_router::{Method, SsrMode, StaticDataMap};
+    use pavex::http::header::CACHE_CONTROL;
+
+    /// A site root of its own for a test, removed when it's dropped
+    struct TempSiteRoot(PathBuf);
+
+    impl TempSiteRoot {
+        fn new(name: &str) -> Self {
+            let path =
+                std::env::temp_dir().join(format!("leptos_pavex_{name}_{}", std::process::id()));
+            let _ = std::fs::remove_dir_all(&path);
+            std::fs::create_dir_all(&path).unwrap();
+            Self(path)
+        }
+
+        fn options(&self) -> LeptosOptions {
+            LeptosOptions::builder()
+                .output_name("app")
+                .site_root(self.0.to_str().unwrap())
+                .build()
+        }
+    }
+
+    impl Drop for TempSiteRoot {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn page(headers: &[(HeaderName, &'static str)]) -> RenderedPage {
+        let mut map = HeaderMap::new();
+        for (name, value) in headers {
+            map.append(name.clone(), HeaderValue::from_static(value));
+        }
+        RenderedPage {
+            status: None,
+            headers: map,
+            html: "<h1>Hi</h1>".to_string(),
+        }
+    }
+
+    #[test]
+    fn static_params_are_percent_encoded() {
+        let params = StaticParamsMap::from([
+            ("id".to_string(), vec!["a b".to_string(), "ü?#".to_string()]),
+            ("rest".to_string(), vec!["/docs/intro page".to_string()]),
+        ]);
+        let paths = expand_static_paths("/post/:id/*rest", Some(&params));
+        assert_eq!(
+            paths,
+            [
+                "/post/a%20b/docs/intro%20page",
+                "/post/%C3%BC%3F%23/docs/intro%20page"
+            ]
+        );
+        for path in &paths {
+            assert_eq!(static_request_head(path).unwrap().target.path(), path);
+        }
+    }
+
+    #[test]
+    fn routes_are_only_rendered_for_params_with_values() {
+        assert_eq!(expand_static_paths("/", None), ["/"]);
+        assert_eq!(expand_static_paths("/about", None), ["/about"]);
+        assert!(expand_static_paths("/post/:id", None).is_empty());
+        assert!(expand_static_paths("/post/:id", Some(&StaticParamsMap::new())).is_empty());
+    }
+
+    #[test]
+    fn invalid_request_paths_are_an_error() {
+        let err = static_request_head("/post/a b").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn pages_for_a_single_visitor_are_not_cacheable() {
+        assert!(page(&[]).is_cacheable());
+        assert!(page(&[(CACHE_CONTROL, "max-age=60")]).is_cacheable());
+        assert!(!page(&[(SET_COOKIE, "session=1")]).is_cacheable());
+        assert!(!page(&[(CACHE_CONTROL, "private")]).is_cacheable());
+        assert!(!page(&[(CACHE_CONTROL, "no-store")]).is_cacheable());
+        let not_found = RenderedPage {
+            status: Some(StatusCode::NOT_FOUND),
+            ..page(&[])
+        };
+        assert!(!not_found.is_cacheable());
+    }
+
+    #[test]
+    fn stored_pages_are_served_with_their_headers() {
+        let root = TempSiteRoot::new("stored_pages");
+        let file_path = static_file_path(&root.options(), "/about").unwrap();
+        let page = RenderedPage {
+            status: Some(StatusCode::ACCEPTED),
+            ..page(&[(CACHE_CONTROL, "max-age=60")])
+        };
+        write_static_page(&file_path, &page).unwrap();
+
+        let res = read_static_page(&file_path).unwrap();
+        assert_eq!(res.status(), StatusCode::ACCEPTED);
+        assert_eq!(res.headers()[CACHE_CONTROL], "max-age=60");
+        assert_eq!(res.headers()[CONTENT_TYPE], "text/html; charset=utf-8");
+        // Only the page and its headers are left, no temporary files
+        let mut files = std::fs::read_dir(file_path.parent().unwrap())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        files.sort();
+        assert_eq!(files, ["index.headers", "index.html"]);
+        assert!(is_page_metadata(&headers_file_path(&file_path)));
+        assert!(!is_page_metadata(&file_path));
+    }
+
+    /// An app that shows how many times it has been rendered
+    fn counting_renderer(renders: Arc<AtomicUsize>) -> AppRenderer {
+        AppRenderer::new(
+            |req_head| {
+                let owner = pass_leptos_context(&RouteType::Component, req_head, || {});
+                AdditionalContextComponent::new(owner)
+            },
+            move |context, _| {
+                let render = renders.fetch_add(1, Ordering::SeqCst) + 1;
+                let app = context
+                    .owner()
+                    .with(|| view! { <p>{render}</p> }.into_any());
+                AppFunction::new(app)
+            },
+        )
+    }
+
+    fn incremental_index(renders: &Arc<AtomicUsize>) -> PavexRouteIndex {
+        PavexRouteIndex::default().with_app_renderer(counting_renderer(renders.clone()))
+    }
+
+    fn serve(routes: &PavexRouteIndex, options: &LeptosOptions, path: &str) -> String {
+        let listing = PavexRouteListing::new(
+            "/post/:id".to_string(),
+            SsrMode::Async,
+            [Method::Get],
+            Some((StaticMode::Incremental, StaticDataMap::new())),
+        );
+        let req_head = static_request_head(path).unwrap();
+        // Only used when the page can't be rendered from a bare request
+        let context = AdditionalContextComponent::new(Owner::new());
+        let app_fn = AppFunction::new(().into_any());
+        block_on(async {
+            let res =
+                render_incremental(options, routes, &listing, req_head, context, app_fn).await;
+            let body = res.into_parts().1.collect().await.unwrap().to_bytes();
+            String::from_utf8(body.to_vec()).unwrap()
+        })
+    }
+
+    #[test]
+    fn incremental_pages_are_rendered_once_and_then_served_from_disk() {
+        let root = TempSiteRoot::new("incremental_once");
+        let renders = Arc::new(AtomicUsize::new(0));
+        let routes = incremental_index(&renders);
+
+        assert!(serve(&routes, &root.options(), "/post/1").contains("<p>1</p>"));
+        assert!(serve(&routes, &root.options(), "/post/1").contains("<p>1</p>"));
+        assert_eq!(renders.load(Ordering::SeqCst), 1);
+        assert!(static_file_path(&root.options(), "/post/1")
+            .unwrap()
+            .exists());
+    }
+
+    #[test]
+    fn invalidated_pages_are_rendered_again() {
+        let root = TempSiteRoot::new("incremental_invalidated");
+        let renders = Arc::new(AtomicUsize::new(0));
+        let routes = incremental_index(&renders);
+        let file_path = static_file_path(&root.options(), "/post/1").unwrap();
+
+        serve(&routes, &root.options(), "/post/1");
+        routes.invalidate_static_path("/post/1");
+        assert!(!file_path.exists());
+        assert!(serve(&routes, &root.options(), "/post/1").contains("<p>2</p>"));
+        assert!(file_path.exists());
+    }
+
+    #[test]
+    fn pages_invalidated_while_rendering_are_not_stored() {
+        let root = TempSiteRoot::new("incremental_outdated");
+        let routes = PavexRouteIndex::default();
+        let pages = &routes.incremental_pages;
+        let file_path = static_file_path(&root.options(), "/post/1").unwrap();
+
+        let generation = pages.generation("/post/1");
+        routes.invalidate_static_path("/post/1");
+        let stored = block_on(pages.store_page("/post/1", &file_path, &page(&[]), generation));
+        assert!(!stored.unwrap());
+        assert!(!file_path.exists());
+        assert!(pages.store.pages.is_empty());
+    }
+
+    #[test]
+    fn only_the_most_recent_incremental_pages_are_kept() {
+        let root = TempSiteRoot::new("incremental_cap");
+        let renders = Arc::new(AtomicUsize::new(0));
+        let routes = incremental_index(&renders).with_max_incremental_pages(2);
+        let file_path = |path| static_file_path(&root.options(), path).unwrap();
+
+        for path in ["/post/1", "/post/2", "/post/3"] {
+            serve(&routes, &root.options(), path);
+        }
+        assert_eq!(routes.incremental_pages.store.pages.len(), 2);
+        assert!(!file_path("/post/1").exists());
+        assert!(file_path("/post/2").exists());
+        assert!(file_path("/post/3").exists());
+    }
+
+    #[test]
+    fn no_incremental_pages_are_kept_with_a_cap_of_zero() {
+        let root = TempSiteRoot::new("incremental_none");
+        let renders = Arc::new(AtomicUsize::new(0));
+        let routes = incremental_index(&renders).with_max_incremental_pages(0);
+
+        serve(&routes, &root.options(), "/post/1");
+        assert!(serve(&routes, &root.options(), "/post/1").contains("<p>2</p>"));
+        assert!(!static_file_path(&root.options(), "/post/1")
+            .unwrap()
+            .exists());
+    }
+
+    #[test]
+    fn static_files_stay_inside_the_site_root() {
+        let root = TempSiteRoot::new("traversal");
+        let options = root.options();
+        assert_eq!(
+            static_file_path(&options, "/post/1"),
+            Some(root.0.join("post/1/index.html"))
+        );
+        assert_eq!(
+            static_file_path(&options, "/"),
+            Some(root.0.join("index.html"))
+        );
+        for path in [
+            "/../etc",
+            "/post/../..",
+            "/post/./1",
+            "/post//1",
+            "/post\\..\\1",
+        ] {
+            assert_eq!(static_file_path(&options, path), None, "{path}");
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn static_files_dont_follow_symlinks_out_of_the_site_root() {
+        let root = TempSiteRoot::new("traversal_symlink");
+        let outside = TempSiteRoot::new("traversal_outside");
+        std::os::unix::fs::symlink(&outside.0, root.0.join("escape")).unwrap();
+        assert_eq!(static_file_path(&root.options(), "/escape/page"), None);
+    }
+}
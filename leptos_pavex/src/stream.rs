@@ -3,13 +3,15 @@ use futures::Stream;
 use http_body::Frame;
 use pavex::response::body::raw::RawBody;
 use pin_project::pin_project;
-use std::pin::Pin;
 use std::error::Error;
+use std::pin::Pin;
+use std::task::Poll;
+use std::time::{Duration, Instant};
 
 #[pin_project]
 pub struct PavexStream<S>
 where
-S: Stream<Item = Result<Bytes, Box<dyn Error + Send + Sync + 'static >>>,
+    S: Stream<Item = Result<Bytes, Box<dyn Error + Send + Sync + 'static>>>,
 {
     #[pin]
     pub inner: S,
@@ -39,10 +41,33 @@ where
     ) -> std::task::Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
         let stream: Pin<&mut S> = self.to_inner_pin();
 
-        S::poll_next(stream, cx).map(|o| o.map(|r| r.map_err(|e| e.into()).map(Frame::data)))
+        S::poll_next(stream, cx).map(|o| o.map(|r| r.map(Frame::data)))
     }
 }
 
+/// Settings for how [`LeptosPavexStream`] batches the chunks of HTML the renderer produces into
+/// frames.
+#[derive(Clone, Copy, Debug)]
+pub struct ChunkBuffering {
+    /// Send a frame once this many bytes have been batched. Set to 0 to send every chunk on its own.
+    pub max_bytes: usize,
+    /// Send a frame once the oldest batched chunk has waited this long while more chunks kept
+    /// coming. Whatever is batched is sent as soon as the renderer has to wait, so nothing is held
+    /// back while it does.
+    pub flush_interval: Duration,
+}
+
+impl Default for ChunkBuffering {
+    fn default() -> Self {
+        Self {
+            max_bytes: 16 * 1024,
+            flush_interval: Duration::from_millis(20),
+        }
+    }
+}
+
+const HEAD_END: &str = "</head>";
+
 #[pin_project]
 pub struct LeptosPavexStream<S>
 where
@@ -79,3 +104,253 @@ where
         S::poll_next(stream, cx).map(|o| o.map(|r| r.map(|d| Frame::data(Bytes::from(d)))))
     }
 }
+
+/// Batches the chunks of HTML from the renderer into larger frames. A frame is sent once the batch
+/// is big enough or old enough, right after the end of the `<head>` so the browser can start
+/// loading scripts and styles, and whenever the renderer has nothing ready. Out-of-order
+/// streaming waits on Suspense between chunks, so every resolved Suspense is sent without delay.
+///
+/// This is the body of every rendered page. [`LeptosPavexStream`] still sends each chunk as its
+/// own frame, for anyone building a body from a stream by hand.
+#[pin_project]
+pub struct BufferedLeptosPavexStream<S>
+where
+    S: Stream<Item = Result<String, std::io::Error>>,
+{
+    #[pin]
+    inner: S,
+    buffering: ChunkBuffering,
+    buffer: String,
+    buffer_started: Option<Instant>,
+    head_sent: bool,
+    error: Option<std::io::Error>,
+    done: bool,
+}
+
+impl<S> BufferedLeptosPavexStream<S>
+where
+    S: Stream<Item = Result<String, std::io::Error>>,
+{
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            buffering: ChunkBuffering::default(),
+            buffer: String::new(),
+            buffer_started: None,
+            head_sent: false,
+            error: None,
+            done: false,
+        }
+    }
+
+    /// Batch the chunks with these settings instead of the default ones.
+    pub fn with_buffering(mut self, buffering: ChunkBuffering) -> Self {
+        self.buffering = buffering;
+        self
+    }
+}
+
+impl<S> RawBody for BufferedLeptosPavexStream<S>
+where
+    S: Stream<Item = Result<String, std::io::Error>>,
+{
+    type Data = Bytes;
+
+    type Error = std::io::Error;
+
+    fn poll_frame(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let mut this = self.project();
+
+        // An error came in while we still had a batch to send, which went out first
+        if let Some(e) = this.error.take() {
+            return Poll::Ready(Some(Err(e)));
+        }
+
+        while !*this.done {
+            match this.inner.as_mut().poll_next(cx) {
+                Poll::Ready(Some(Ok(chunk))) => {
+                    if this.buffer_started.is_none() {
+                        *this.buffer_started = Some(Instant::now());
+                    }
+                    let searched = this.buffer.len();
+                    this.buffer.push_str(&chunk);
+                    if !*this.head_sent {
+                        if let Some(end) = find_head_end(this.buffer, searched) {
+                            *this.head_sent = true;
+                            let rest = this.buffer.split_off(end);
+                            let frame = take_frame(this.buffer, this.buffer_started);
+                            if !rest.is_empty() {
+                                *this.buffer = rest;
+                                *this.buffer_started = Some(Instant::now());
+                            }
+                            return frame;
+                        }
+                    }
+
+                    let is_full = this.buffer.len() >= this.buffering.max_bytes;
+                    let is_old = this
+                        .buffer_started
+                        .is_some_and(|started| started.elapsed() >= this.buffering.flush_interval);
+                    if is_full || is_old {
+                        return take_frame(this.buffer, this.buffer_started);
+                    }
+                }
+                Poll::Ready(Some(Err(e))) => {
+                    if this.buffer.is_empty() {
+                        return Poll::Ready(Some(Err(e)));
+                    }
+                    *this.error = Some(e);
+                    return take_frame(this.buffer, this.buffer_started);
+                }
+                Poll::Ready(None) => *this.done = true,
+                // The renderer is waiting on something, so send what we have
+                Poll::Pending => {
+                    if this.buffer.is_empty() {
+                        return Poll::Pending;
+                    }
+                    return take_frame(this.buffer, this.buffer_started);
+                }
+            }
+        }
+
+        if this.buffer.is_empty() {
+            Poll::Ready(None)
+        } else {
+            take_frame(this.buffer, this.buffer_started)
+        }
+    }
+}
+
+/// Where the `</head>` tag in the buffer ends, looking only at what came in after the first
+/// `searched` bytes. The tag can be split across chunks, so the search starts just far enough
+/// back to catch one that began at the end of the previous chunk.
+fn find_head_end(buffer: &str, searched: usize) -> Option<usize> {
+    let mut start = searched.saturating_sub(HEAD_END.len() - 1);
+    while !buffer.is_char_boundary(start) {
+        start -= 1;
+    }
+    buffer[start..]
+        .find(HEAD_END)
+        .map(|i| start + i + HEAD_END.len())
+}
+
+/// Send everything that's been batched as a single frame.
+fn take_frame(
+    buffer: &mut String,
+    buffer_started: &mut Option<Instant>,
+) -> Poll<Option<Result<Frame<Bytes>, std::io::Error>>> {
+    *buffer_started = None;
+    Poll::Ready(Some(Ok(Frame::data(Bytes::from(std::mem::take(buffer))))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::stream;
+    use http_body_util::BodyExt;
+
+    const UNBATCHED: ChunkBuffering = ChunkBuffering {
+        max_bytes: usize::MAX,
+        flush_interval: Duration::MAX,
+    };
+
+    fn collect_frames<S>(body: BufferedLeptosPavexStream<S>) -> Vec<String>
+    where
+        S: Stream<Item = Result<String, std::io::Error>>,
+    {
+        let mut body = Box::pin(body);
+        let mut frames = Vec::new();
+        while let Some(frame) = futures::executor::block_on(body.frame()) {
+            let data = frame.unwrap().into_data().unwrap();
+            frames.push(String::from_utf8(data.to_vec()).unwrap());
+        }
+        frames
+    }
+
+    fn frames_with(buffering: ChunkBuffering, chunks: &[&str]) -> Vec<String> {
+        let chunks = chunks
+            .iter()
+            .map(|chunk| Ok(chunk.to_string()))
+            .collect::<Vec<_>>();
+        collect_frames(
+            BufferedLeptosPavexStream::new(stream::iter(chunks)).with_buffering(buffering),
+        )
+    }
+
+    fn frames(chunks: &[&str]) -> Vec<String> {
+        frames_with(UNBATCHED, chunks)
+    }
+
+    #[test]
+    fn head_is_sent_on_its_own() {
+        assert_eq!(
+            frames(&["<html><head></head><body>", "page</body></html>"]),
+            ["<html><head></head>", "<body>page</body></html>"]
+        );
+    }
+
+    #[test]
+    fn head_end_split_across_chunks_is_found() {
+        assert_eq!(
+            frames(&["<html><head></he", "ad><body>", "page</body></html>"]),
+            ["<html><head></head>", "<body>page</body></html>"]
+        );
+    }
+
+    #[test]
+    fn head_end_is_found_after_multibyte_characters() {
+        assert_eq!(find_head_end("<title>é</he", 14), None);
+        assert_eq!(find_head_end("<title>é</head>", 14), Some(16));
+    }
+
+    #[test]
+    fn full_batches_are_sent() {
+        let buffering = ChunkBuffering {
+            max_bytes: 8,
+            ..UNBATCHED
+        };
+        assert_eq!(
+            frames_with(
+                buffering,
+                &["<head></head>", "aaaa", "bbbb", "cc", "dd", "ee"]
+            ),
+            ["<head></head>", "aaaabbbb", "ccddee"]
+        );
+    }
+
+    #[test]
+    fn old_batches_are_sent() {
+        let buffering = ChunkBuffering {
+            flush_interval: Duration::ZERO,
+            ..UNBATCHED
+        };
+        assert_eq!(
+            frames_with(buffering, &["<head></head>", "a", "b"]),
+            ["<head></head>", "a", "b"]
+        );
+    }
+
+    #[test]
+    fn batches_are_sent_while_the_renderer_waits() {
+        // The renderer has "a" ready, then waits before "b"
+        let mut chunks = vec![
+            Poll::Ready(Some(Ok("<head></head>a".to_string()))),
+            Poll::Pending,
+            Poll::Ready(Some(Ok("b".to_string()))),
+            Poll::Ready(None),
+        ]
+        .into_iter();
+        let inner = stream::poll_fn(move |cx| {
+            let chunk = chunks.next().unwrap_or(Poll::Ready(None));
+            if chunk.is_pending() {
+                cx.waker().wake_by_ref();
+            }
+            chunk
+        });
+        let body = BufferedLeptosPavexStream::new(inner).with_buffering(UNBATCHED);
+        assert_eq!(collect_frames(body), ["<head></head>", "a", "b"]);
+    }
+}
@@ -1,19 +1,264 @@
 use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2, TokenTree};
 use proc_macro_error::proc_macro_error;
-use syn::__private::ToTokens;
+use quote::quote;
+use std::path::Path;
+use syn::{Expr, ExprLit, File, Item, Lit, LitStr, MetaNameValue, Type};
 
+/// The prefix server functions are mounted under when nothing else is configured.
+const DEFAULT_PREFIX: &str = "/api";
+
+/// Declares a server function for Pavex, like Leptos's own `#[server]`.
+///
+/// The default prefix of server function URLs is `/api`. Change it for every server function in a
+/// crate with the `LEPTOS_PAVEX_SERVER_FN_PREFIX` environment variable, or with Cargo metadata in
+/// the crate's `Cargo.toml`:
+///
+/// ```toml
+/// [package.metadata.leptos_pavex]
+/// server_fn_prefix = "/_server"
+/// ```
+///
+/// The environment variable wins over the metadata. Cargo doesn't rebuild when it changes, so run
+/// `cargo clean -p` on the crate after changing it. A single server function can still pick its
+/// own prefix with `#[server(prefix = "/other")]`.
+///
+/// The prefix is part of the path each server function is compiled with, so
+/// `generate_leptos_routes` registers them and `handle_server_fns` finds them under it without
+/// any other configuration.
+///
+/// Request bodies are limited to the app's default, set with
+/// `PavexRouteIndex::with_body_limit`, and aren't limited unless it's set. A server function can
+/// set its own limit with `#[server(body_limit = "10MB")]`, e.g. to allow bigger uploads. The limit
+/// takes a number of bytes with an optional `KB`, `MB` or `GB` suffix, counted in multiples of
+/// 1024.
 #[proc_macro_attribute]
 #[proc_macro_error]
 pub fn server(args: proc_macro::TokenStream, s: TokenStream) -> TokenStream {
-    match server_fn_macro::server_macro_impl(
-        args.into(),
-        s.into(),
+    match server_impl(args.into(), s.into()) {
+        Ok(s) => s.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn server_impl(args: TokenStream2, s: TokenStream2) -> syn::Result<TokenStream2> {
+    let (args, body_limit) = take_body_limit(args)?;
+    let max_size = body_limit
+        .map(|limit| {
+            parse_size(&limit.value()).ok_or_else(|| {
+                syn::Error::new(limit.span(), "expected a size like \"512KB\" or \"10MB\"")
+            })
+        })
+        .transpose()?;
+    let mut expanded = server_fn_macro::server_macro_impl(
+        args,
+        s,
         Some(syn::parse_quote!(leptos::server_fn)),
-        "/api",
+        &default_prefix(),
         Some(syn::parse_quote!(::leptos_pavex::request::PavexRequest)),
         Some(syn::parse_quote!(::leptos_pavex::response::PavexResponse)),
-    ) {
-        Err(e) => e.to_compile_error().into(),
-        Ok(s) => s.to_token_stream().into(),
+    )?;
+    if let Some(max_size) = max_size {
+        let server_fn = server_fn_type(&expanded)?;
+        expanded.extend(register_body_limit(&server_fn, max_size));
+    }
+    Ok(expanded)
+}
+
+/// Split the `body_limit` argument, which `server_fn_macro` doesn't know about, off the rest of
+/// the arguments.
+fn take_body_limit(args: TokenStream2) -> syn::Result<(TokenStream2, Option<LitStr>)> {
+    let mut rest = Vec::new();
+    let mut body_limit = None;
+    for arg in split_args(args) {
+        match syn::parse2::<MetaNameValue>(arg.clone()) {
+            Ok(meta) if meta.path.is_ident("body_limit") => match meta.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(limit),
+                    ..
+                }) => body_limit = Some(limit),
+                value => {
+                    return Err(syn::Error::new_spanned(
+                        value,
+                        "expected a size like \"512KB\" or \"10MB\"",
+                    ))
+                }
+            },
+            _ => rest.push(arg),
+        }
+    }
+    Ok((quote!(#(#rest),*), body_limit))
+}
+
+/// The arguments, split on the commas between them.
+fn split_args(args: TokenStream2) -> Vec<TokenStream2> {
+    let mut split = vec![Vec::new()];
+    for token in args {
+        match &token {
+            TokenTree::Punct(p) if p.as_char() == ',' => split.push(Vec::new()),
+            _ => split.last_mut().unwrap().push(token),
+        }
+    }
+    split
+        .into_iter()
+        .filter(|arg| !arg.is_empty())
+        .map(|arg| arg.into_iter().collect())
+        .collect()
+}
+
+/// The type `server_fn_macro` implemented `ServerFn` for: the struct it generated for the
+/// function, in its custom wrapper if it has one. Taking it from the expansion keeps it the same
+/// however the struct was named.
+fn server_fn_type(expanded: &TokenStream2) -> syn::Result<Type> {
+    let file = syn::parse2::<File>(expanded.clone())?;
+    file.items
+        .into_iter()
+        .find_map(|item| match item {
+            Item::Impl(item) => item
+                .trait_
+                .as_ref()
+                .and_then(|(_, path, _)| path.segments.last())
+                .filter(|segment| segment.ident == "ServerFn")
+                .map(|_| *item.self_ty),
+            _ => None,
+        })
+        .ok_or_else(|| syn::Error::new(Span::call_site(), "no `ServerFn` impl was generated"))
+}
+
+/// Submit the body limit of the server function, so `handle_server_fns` can find it by the
+/// function's path. Only the server needs it, and `leptos_pavex` is only there with `ssr`.
+fn register_body_limit(server_fn: &Type, max_size: usize) -> TokenStream2 {
+    quote! {
+        #[cfg(feature = "ssr")]
+        ::leptos::server_fn::inventory::submit! {
+            ::leptos_pavex::server_fn::ServerFnBodyLimit {
+                path: <#server_fn as ::leptos::server_fn::ServerFn>::PATH,
+                max_size: #max_size,
+            }
+        }
+    }
+}
+
+/// Parse a size like `10MB` into bytes.
+fn parse_size(size: &str) -> Option<usize> {
+    let size = size.trim();
+    let digits = size
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(size.len());
+    let (number, unit) = size.split_at(digits);
+    let multiplier = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "KB" | "KIB" => 1024,
+        "MB" | "MIB" => 1024 * 1024,
+        "GB" | "GIB" => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+    number.parse::<usize>().ok()?.checked_mul(multiplier)
+}
+
+fn default_prefix() -> String {
+    if let Ok(prefix) = std::env::var("LEPTOS_PAVEX_SERVER_FN_PREFIX") {
+        return prefix;
+    }
+    metadata_prefix().unwrap_or_else(|| DEFAULT_PREFIX.to_string())
+}
+
+/// Read `package.metadata.leptos_pavex.server_fn_prefix` from the manifest of the crate the macro
+/// is expanded in.
+fn metadata_prefix() -> Option<String> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").ok()?;
+    let manifest = std::fs::read_to_string(Path::new(&manifest_dir).join("Cargo.toml")).ok()?;
+    let manifest = manifest.parse::<toml::Table>().ok()?;
+    manifest
+        .get("package")?
+        .get("metadata")?
+        .get("leptos_pavex")?
+        .get("server_fn_prefix")?
+        .as_str()
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::ToTokens;
+
+    #[test]
+    fn the_body_limit_is_split_off_the_other_arguments() {
+        let (rest, body_limit) = take_body_limit(quote!(
+            endpoint = "upload",
+            body_limit = "10MB",
+            input = Wrapper<A, B>
+        ))
+        .unwrap();
+        assert_eq!(body_limit.unwrap().value(), "10MB");
+        assert_eq!(
+            rest.to_string(),
+            quote!(endpoint = "upload", input = Wrapper<A, B>).to_string()
+        );
+    }
+
+    #[test]
+    fn arguments_without_a_body_limit_are_left_alone() {
+        let args = quote!(GetPosts, "/api", "GetJson");
+        let (rest, body_limit) = take_body_limit(args.clone()).unwrap();
+        assert!(body_limit.is_none());
+        assert_eq!(rest.to_string(), args.to_string());
+    }
+
+    #[test]
+    fn body_limits_have_to_be_strings() {
+        assert!(take_body_limit(quote!(body_limit = 1024)).is_err());
+    }
+
+    #[test]
+    fn sizes_are_counted_in_multiples_of_1024() {
+        assert_eq!(parse_size("512"), Some(512));
+        assert_eq!(parse_size("512B"), Some(512));
+        assert_eq!(parse_size("2KB"), Some(2 * 1024));
+        assert_eq!(parse_size(" 10 mb "), Some(10 * 1024 * 1024));
+        assert_eq!(parse_size("1GiB"), Some(1024 * 1024 * 1024));
+        assert_eq!(parse_size("10TB"), None);
+        assert_eq!(parse_size("MB"), None);
+    }
+
+    #[test]
+    fn the_server_fn_type_is_taken_from_the_expansion() {
+        let expanded = quote! {
+            pub struct RenamedUpload { pub data: String }
+            impl From<RenamedUpload> for String {
+                fn from(value: RenamedUpload) -> Self { value.data }
+            }
+            impl leptos::server_fn::ServerFn for Rkyv<RenamedUpload> {
+                const PATH: &'static str = "/api/renamed";
+            }
+            pub async fn upload(data: String) -> Result<usize, ServerFnError> {
+                Ok(data.len())
+            }
+        };
+        let server_fn = server_fn_type(&expanded).unwrap();
+        assert_eq!(
+            server_fn.to_token_stream().to_string(),
+            quote!(Rkyv<RenamedUpload>).to_string()
+        );
+        let without_impl = quote!(
+            pub struct Upload;
+        );
+        assert!(server_fn_type(&without_impl).is_err());
+    }
+
+    #[test]
+    fn body_limits_are_only_registered_on_the_server() {
+        let registration = register_body_limit(&syn::parse_quote!(Upload), 1024);
+        let expected = quote! {
+            #[cfg(feature = "ssr")]
+            ::leptos::server_fn::inventory::submit! {
+                ::leptos_pavex::server_fn::ServerFnBodyLimit {
+                    path: <Upload as ::leptos::server_fn::ServerFn>::PATH,
+                    max_size: 1024usize,
+                }
+            }
+        };
+        assert_eq!(registration.to_string(), expected.to_string());
     }
 }
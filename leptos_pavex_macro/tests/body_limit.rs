@@ -0,0 +1,39 @@
+use leptos::server_fn::{inventory, ServerFn, ServerFnError};
+use leptos_pavex::server_fn::ServerFnBodyLimit;
+use leptos_pavex_macro::server;
+
+#[server(body_limit = "2KB")]
+pub async fn upload(data: String) -> Result<usize, ServerFnError> {
+    Ok(data.len())
+}
+
+#[server(name = RenamedUpload, endpoint = "renamed", body_limit = "1MB")]
+pub async fn upload_with_another_name(data: String) -> Result<usize, ServerFnError> {
+    Ok(data.len())
+}
+
+#[server]
+pub async fn unlimited(data: String) -> Result<usize, ServerFnError> {
+    Ok(data.len())
+}
+
+fn declared_body_limit(path: &str) -> Option<usize> {
+    inventory::iter::<ServerFnBodyLimit>
+        .into_iter()
+        .find(|limit| limit.path == path)
+        .map(|limit| limit.max_size)
+}
+
+#[cfg(feature = "ssr")]
+#[test]
+fn body_limits_are_registered_under_the_server_fn_path() {
+    assert_eq!(declared_body_limit(Upload::PATH), Some(2 * 1024));
+    assert_eq!(declared_body_limit(RenamedUpload::PATH), Some(1024 * 1024));
+    assert_eq!(declared_body_limit(Unlimited::PATH), None);
+}
+
+#[cfg(not(feature = "ssr"))]
+#[test]
+fn body_limits_are_only_registered_on_the_server() {
+    assert_eq!(declared_body_limit(Upload::PATH), None);
+}